@@ -0,0 +1,22 @@
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MinefieldState {
+    Play,           // normal player interaction with the game field
+    GameOver(bool), // bool is for win state, true for win, false for loss
+}
+
+/// What the player can see on top of a block
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MinefieldBlock {
+    Clear,
+    Block,
+    Flag(u32), // how many mines the player thinks are in the block
+    Question,
+}
+
+/// What is underneath a block once it has been cleared
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MinefieldItem {
+    Blank,
+    Number(u32),
+    Mine(u32), // how many mines are in the block
+}
@@ -0,0 +1,1464 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    Grid, LineClue, MinefieldBlock, MinefieldItem, MinefieldState, Neighbourhood, Puzzle, Shape,
+    Topology, Vec2,
+    cell::Cell,
+    chunks::{self, CHUNK_SIZE, Chunks},
+};
+
+/// The most mines a block can hold, when more than one are allowed
+pub const MAX_MINES_PER_BLOCK: u32 = 3;
+
+/// The most floors a board can be stacked into
+pub const MAX_FLOORS: i32 = 3;
+
+// Where an endless board starts, with the blocks around it left clear of mines
+pub(crate) const ENDLESS_START: Vec2 = Vec2::new(0, 0);
+
+/// The state of every block on a minefield, along with the rules for changing it
+#[derive(Clone)]
+pub struct Board {
+    size: Vec2, // of a single floor
+    floors: i32,
+    grid: Grid,
+    neighbourhood: Neighbourhood,
+    topology: Topology,
+    // Each floor's rows follow on from the rows of the floor above it
+    cells: Vec<Cell>,
+    chunks: Chunks, // in place of the cells on an endless board
+    flag_count: u32,
+    mine_total: u32, // mines on every floor, which is never counted on an endless board
+    max_mines_per_block: u32,
+    covered_safe_blocks: u32, // the game is won once this reaches 0
+    cleared_blocks: u32,      // safe blocks the player has cleared, the score on an endless board
+    // None for the standard game, where the first mine ends it
+    lives_per_game: Option<u32>,
+    lives: u32,
+    question_marks: bool,
+    // Every block is queued at most once per cascade, so this never grows past the block count
+    blocks_to_clear: Vec<Vec2>,
+    cascade_layer_start: usize, // blocks before this in blocks_to_clear have been cleared
+}
+
+impl Board {
+    /// Create a board with block `size` (w x h), with no mines and every block covered
+    pub fn new(size: Vec2) -> Self {
+        let block_count = (size.x * size.y) as usize;
+        Self {
+            size,
+            floors: 1,
+            grid: Grid::default(),
+            neighbourhood: Neighbourhood::default(),
+            topology: Topology::default(),
+            cells: vec![Cell::default(); block_count],
+            chunks: Chunks::default(),
+            flag_count: 0,
+            mine_total: 0,
+            max_mines_per_block: 1,
+            covered_safe_blocks: block_count as u32,
+            cleared_blocks: 0,
+            lives_per_game: None,
+            lives: 0,
+            question_marks: true,
+            blocks_to_clear: Vec::with_capacity(block_count),
+            cascade_layer_start: 0,
+        }
+    }
+
+    /// Create a board with block `size` (w x h) and mines at exactly the positions in `mines`,
+    /// for tests here and in the crates using the `test-util` feature
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn with_mines(size: Vec2, mines: &[Vec2]) -> Self {
+        let mut board = Self::new(size);
+        for &mine in mines {
+            board.set_mine(mine, true);
+        }
+        board
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    pub fn set_size(&mut self, size: Vec2) -> &mut Self {
+        let block_count = (size.x * size.y * self.floors) as usize;
+        self.size = size;
+        self.cells = vec![Cell::default(); block_count];
+        self.mine_total = 0;
+        self.blocks_to_clear = Vec::with_capacity(block_count);
+        self.reset();
+        self
+    }
+
+    /// How many floors the board has, which is always 1 on an endless board
+    pub fn floors(&self) -> i32 {
+        if self.topology == Topology::Endless {
+            return 1;
+        }
+        self.floors
+    }
+
+    /// Stack `floors` floors of the board's size on top of each other, with the mines around each
+    /// block counted on the floors above and below it as well, ready for [`Board::gen_mines`]
+    ///
+    /// A block on a floor below the top one is found below the floor above it, so that floor `f`
+    /// starts at row `f * h`. Every floor has the same holes.
+    pub fn set_floors(&mut self, floors: i32) -> &mut Self {
+        debug_assert!((1..=MAX_FLOORS).contains(&floors));
+        let playable: Vec<bool> = (0..self.size.y)
+            .flat_map(|y| (0..self.size.x).map(move |x| Vec2::new(x, y)))
+            .map(|block_pos| self.cells[self.block_pos_to_index(block_pos)].is_playable())
+            .collect();
+        let width = self.size.x;
+        self.floors = floors;
+        self.set_size(self.size);
+        self.set_holes(|block_pos| playable[(block_pos.x + block_pos.y * width) as usize]);
+        self
+    }
+
+    /// Which floor `block_pos` is on, counting down from 0 for the top floor
+    pub fn floor_of(&self, block_pos: Vec2) -> i32 {
+        if self.floors() == 1 {
+            return 0;
+        }
+        block_pos.y.div_euclid(self.size.y)
+    }
+
+    /// Position of the top left block of `floor`
+    pub fn floor_origin(&self, floor: i32) -> Vec2 {
+        Vec2::new(0, floor * self.size.y)
+    }
+
+    /// The block in line with `block_pos` `step` floors down, or `None` past the top or bottom
+    /// floor
+    pub fn floor_step(&self, block_pos: Vec2, step: i32) -> Option<Vec2> {
+        let floor = self.floor_of(block_pos) + step;
+        (0..self.floors())
+            .contains(&floor)
+            .then(|| block_pos + self.floor_origin(step))
+    }
+
+    /// Resize the board to fit `shape`, leaving holes where it has no blocks
+    pub fn set_shape(&mut self, shape: &Shape) -> &mut Self {
+        self.set_size(shape.size());
+        self.set_holes(|block_pos| shape.is_playable(block_pos));
+        self
+    }
+
+    /// Leave a hole on every floor wherever `is_playable` is false for the position of a block on
+    /// its floor
+    fn set_holes(&mut self, is_playable: impl Fn(Vec2) -> bool) {
+        for block_pos in self.all_blocks() {
+            let floor_pos = block_pos - self.floor_origin(self.floor_of(block_pos));
+            let index = self.block_pos_to_index(block_pos);
+            self.cells[index].set_playable(is_playable(floor_pos));
+        }
+        self.count_covered_safe_blocks();
+    }
+
+    /// Lay out `puzzle`, with its mines placed and its starting blocks cleared as though the player
+    /// had revealed them
+    ///
+    /// Puzzles are designed for a single floor of a square grid with solid edges, the standard
+    /// neighbourhood and one mine per block, so the board goes back to those.
+    pub fn set_puzzle(&mut self, puzzle: &Puzzle) -> &mut Self {
+        self.floors = 1;
+        self.grid = Grid::Square;
+        self.topology = Topology::Bounded;
+        self.neighbourhood = Neighbourhood::Standard;
+        self.max_mines_per_block = 1;
+        self.set_shape(puzzle.shape());
+
+        let size = self.size;
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let block_pos = Vec2::new(x, y);
+                if puzzle.has_mine(block_pos) {
+                    self.set_mine(block_pos, true);
+                }
+            }
+        }
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let block_pos = Vec2::new(x, y);
+                if puzzle.starts_cleared(block_pos) {
+                    self.reveal_block(block_pos);
+                    while self.is_cascading() {
+                        self.cascade_step(|_, _| ());
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    pub fn grid(&self) -> Grid {
+        self.grid
+    }
+
+    /// Arrange the blocks in `grid`, counting the mines around each block again
+    pub fn set_grid(&mut self, grid: Grid) -> &mut Self {
+        self.grid = grid;
+        self.count_all_adjacent_mines();
+        self
+    }
+
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        self.neighbourhood
+    }
+
+    /// Count the mines in `neighbourhood` around each block, cascading through it as well
+    pub fn set_neighbourhood(&mut self, neighbourhood: Neighbourhood) -> &mut Self {
+        self.neighbourhood = neighbourhood;
+        self.count_all_adjacent_mines();
+        self
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Join the edges of the board up as in `topology`, counting the mines around each block again
+    ///
+    /// A hex grid only joins up top to bottom with an even number of rows, since the rows alternate,
+    /// so with an odd number only its sides join up.
+    /// Going to or from an endless board starts it again with every block covered, ready for
+    /// [`Board::gen_mines`].
+    pub fn set_topology(&mut self, topology: Topology) -> &mut Self {
+        let was_endless = self.topology == Topology::Endless;
+        self.topology = topology;
+        if was_endless != (topology == Topology::Endless) {
+            self.reset();
+        }
+        self.count_all_adjacent_mines();
+        self
+    }
+
+    /// Whether the top and bottom edges can be joined up, which a hex grid only can with an even
+    /// number of rows
+    pub fn wraps_vertically(&self) -> bool {
+        self.grid != Grid::Hex || self.size.y % 2 == 0
+    }
+
+    /// Safe blocks the player has cleared since the board was last covered, which is the score on
+    /// an endless board
+    pub fn cleared_blocks(&self) -> u32 {
+        self.cleared_blocks
+    }
+
+    pub fn max_mines_per_block(&self) -> u32 {
+        self.max_mines_per_block
+    }
+
+    /// Let [`Board::gen_mines`] put up to `max_mines_per_block` mines in a block, and the player put
+    /// as many flags on one
+    pub fn set_max_mines_per_block(&mut self, max_mines_per_block: u32) -> &mut Self {
+        debug_assert!((1..=MAX_MINES_PER_BLOCK).contains(&max_mines_per_block));
+        self.max_mines_per_block = max_mines_per_block;
+        self
+    }
+
+    pub fn lives_per_game(&self) -> Option<u32> {
+        self.lives_per_game
+    }
+
+    /// Start each game with `lives_per_game` lives, with a revealed mine costing one of them
+    /// instead of ending the game, or go back to the standard game for `None`
+    ///
+    /// The mine is flagged as defused, and the game is only lost once the last life is gone.
+    pub fn set_lives_per_game(&mut self, lives_per_game: Option<u32>) -> &mut Self {
+        self.lives_per_game = lives_per_game;
+        self.lives = lives_per_game.unwrap_or(0);
+        self
+    }
+
+    /// Lives left in this game, or `None` for the standard game
+    pub fn lives(&self) -> Option<u32> {
+        self.lives_per_game.map(|_| self.lives)
+    }
+
+    pub fn set_question_marks(&mut self, question_marks: bool) -> &mut Self {
+        self.question_marks = question_marks;
+        self
+    }
+
+    /// Place mines using `next_random` as the source of random numbers, leaving out any holes
+    ///
+    /// An endless board only takes a seed, placing mines from it as more of the board is explored,
+    /// and starts with the blocks around [`ENDLESS_START`] cleared.
+    pub fn gen_mines(&mut self, mut next_random: impl FnMut() -> i32) {
+        if self.topology == Topology::Endless {
+            self.chunks.clear(next_random() as u32);
+            self.explore(ENDLESS_START);
+            self.reveal_block(ENDLESS_START);
+            while self.is_cascading() {
+                self.cascade_step(|_, _| ());
+            }
+
+            // The opening is free, so it doesn't count towards the score
+            self.cleared_blocks = 0;
+            return;
+        }
+
+        self.mine_total = 0;
+        for cell in &mut self.cells {
+            let rand_num = next_random().unsigned_abs();
+
+            // 1/8 chance, avoids division
+            if cell.is_playable() && rand_num < i32::MAX as u32 >> 3 {
+                // The low bits are still random enough to pick how many mines there are
+                cell.set_mines(1 + rand_num % self.max_mines_per_block);
+                self.mine_total += cell.mines();
+            } else {
+                cell.set_mines(0);
+            }
+        }
+
+        self.count_all_adjacent_mines();
+        self.count_covered_safe_blocks();
+    }
+
+    /// Count the mines around every block once, rather than each time a block is cleared
+    fn count_all_adjacent_mines(&mut self) {
+        if self.topology == Topology::Endless {
+            for block_pos in self.loaded_blocks() {
+                let adjacent_mines = self.count_adjacent_mines(block_pos);
+                self.cell_mut(block_pos).set_adjacent_mines(adjacent_mines);
+            }
+            return;
+        }
+
+        for block_pos in self.all_blocks() {
+            let adjacent_mines = self.count_adjacent_mines(block_pos);
+            let index = self.block_pos_to_index(block_pos);
+            self.cells[index].set_adjacent_mines(adjacent_mines);
+        }
+    }
+
+    fn count_adjacent_mines(&self, block_pos: Vec2) -> u32 {
+        self.neighbours(block_pos)
+            .map(|neighbour| self.mine_count(neighbour))
+            .sum()
+    }
+
+    fn count_covered_safe_blocks(&mut self) {
+        // An endless board never runs out of blocks to clear
+        if self.topology == Topology::Endless {
+            self.covered_safe_blocks = u32::MAX;
+            return;
+        }
+
+        self.covered_safe_blocks = self
+            .cells
+            .iter()
+            .filter(|cell| {
+                cell.is_playable() && !cell.is_mine() && cell.block() != MinefieldBlock::Clear
+            })
+            .count() as u32;
+    }
+
+    /// Cover every block again, keeping the mines where they are
+    pub fn reset(&mut self) {
+        self.clear_cascade();
+        for cell in &mut self.cells {
+            cell.set_block(MinefieldBlock::Block);
+        }
+        self.chunks.clear(self.chunks.seed());
+        self.flag_count = 0;
+        self.cleared_blocks = 0;
+        self.lives = self.lives_per_game.unwrap_or(0);
+        self.count_covered_safe_blocks();
+    }
+
+    pub fn contains(&self, block_pos: Vec2) -> bool {
+        let floor = self.floor_of(block_pos);
+        (0..self.floors()).contains(&floor)
+            && self.floor_contains(block_pos - self.floor_origin(floor))
+    }
+
+    /// Whether `block_pos`, counted from the top left of a floor, is on that floor
+    pub fn floor_contains(&self, block_pos: Vec2) -> bool {
+        self.topology.block_at(self.size, block_pos) == Some(block_pos)
+    }
+
+    /// Whether `block_pos` is on the board and not a hole in its shape
+    pub fn is_playable(&self, block_pos: Vec2) -> bool {
+        // An endless board has no holes, and looking at its cells could mean making them
+        self.topology == Topology::Endless
+            || (self.contains(block_pos) && self.cell(block_pos).is_playable())
+    }
+
+    /// The playable block closest to `block_pos`, which is `block_pos` itself if it's playable
+    pub fn nearest_playable(&self, block_pos: Vec2) -> Vec2 {
+        if self.is_playable(block_pos) {
+            return block_pos;
+        }
+
+        self.all_blocks()
+            .into_iter()
+            .filter(|&block| self.is_playable(block))
+            .min_by_key(|&block| {
+                let offset = block - block_pos;
+                offset.x * offset.x + offset.y * offset.y
+            })
+            .unwrap_or(block_pos)
+    }
+
+    fn neighbour_offsets(&self, row: i32) -> &'static [Vec2] {
+        self.neighbourhood.offsets(self.grid, row)
+    }
+
+    /// How many neighbour offsets there are from `block_pos`, through its own floor and then the
+    /// floors above and below it
+    fn neighbour_count(&self, block_pos: Vec2) -> usize {
+        let floor_row = block_pos.y - self.floor_origin(self.floor_of(block_pos)).y;
+        let offset_count = self.neighbour_offsets(floor_row).len();
+        if self.floors() == 1 {
+            return offset_count;
+        }
+
+        // The floors above and below add the block in line with this one as well
+        offset_count + 2 * (offset_count + 1)
+    }
+
+    /// The blocks around `block_pos`, each only once
+    pub fn neighbours(&self, block_pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        (0..self.neighbour_count(block_pos)).filter_map(move |i| self.neighbour(block_pos, i))
+    }
+
+    /// The block at the `i`th neighbour offset from `block_pos`, if it's a distinct block and not a
+    /// hole
+    ///
+    /// Taking an index rather than going through [`Board::neighbours`] lets callers change the
+    /// board between neighbours.
+    fn neighbour(&self, block_pos: Vec2, i: usize) -> Option<Vec2> {
+        let neighbour = self.neighbour_at(block_pos, i)?;
+        if !self.is_playable(neighbour) {
+            return None;
+        }
+
+        // A wrapped board no wider or taller than the neighbourhood can reach the same block twice,
+        // or come back around to this one
+        let span = 2 * self.neighbourhood.reach(self.grid);
+        let seen = self.topology == Topology::Toroidal
+            && (self.size.x <= span || self.size.y <= span)
+            && (neighbour == block_pos
+                || (0..i).any(|j| self.neighbour_at(block_pos, j) == Some(neighbour)));
+        (!seen).then_some(neighbour)
+    }
+
+    /// The block at the `i`th neighbour offset from `block_pos`, wrapping around the edges of its
+    /// floor if they join up, or `None` if it's past an edge
+    ///
+    /// Past the offsets on its own floor come the floors above and below, each with the block in
+    /// line with `block_pos` followed by the same offsets again. Floors never wrap around.
+    fn neighbour_at(&self, block_pos: Vec2, i: usize) -> Option<Vec2> {
+        let floor = self.floor_of(block_pos);
+        let floor_pos = block_pos - self.floor_origin(floor);
+        let offsets = self.neighbour_offsets(floor_pos.y);
+        let (floor_step, offset) = match i.checked_sub(offsets.len()) {
+            None => (0, offsets[i]),
+            Some(i) => {
+                let floor_step = if i <= offsets.len() { -1 } else { 1 };
+                let offset = match i % (offsets.len() + 1) {
+                    0 => Vec2::new(0, 0),
+                    i => offsets[i - 1],
+                };
+                (floor_step, offset)
+            }
+        };
+
+        let neighbour_floor = floor + floor_step;
+        if !(0..self.floors()).contains(&neighbour_floor) {
+            return None;
+        }
+        let neighbour_pos = floor_pos + offset;
+        if self.topology == Topology::Toroidal
+            && !self.wraps_vertically()
+            && !(0..self.size.y).contains(&neighbour_pos.y)
+        {
+            return None;
+        }
+        let neighbour = self.topology.block_at(self.size, neighbour_pos)?;
+        Some(neighbour + self.floor_origin(neighbour_floor))
+    }
+
+    fn block_pos_to_index(&self, block_pos: Vec2) -> usize {
+        (block_pos.x + block_pos.y * self.size.x) as usize
+    }
+
+    /// The cell for `block_pos`, made on the spot if it's in a chunk of an endless board which
+    /// isn't loaded
+    fn cell(&self, block_pos: Vec2) -> Cell {
+        if self.topology != Topology::Endless {
+            return self.cells[self.block_pos_to_index(block_pos)];
+        }
+        self.chunks.cell(block_pos).unwrap_or_else(|| {
+            let mut cell = self.new_endless_cell(block_pos);
+            cell.set_block(self.chunks.packed_block(block_pos));
+            cell
+        })
+    }
+
+    /// The cell for `block_pos`, loading its chunk first on an endless board
+    fn cell_mut(&mut self, block_pos: Vec2) -> &mut Cell {
+        if self.topology != Topology::Endless {
+            let index = self.block_pos_to_index(block_pos);
+            return &mut self.cells[index];
+        }
+        self.load_chunk(chunks::chunk_of(block_pos).0);
+        self.chunks
+            .cell_mut(block_pos)
+            .expect("the chunk was just loaded")
+    }
+
+    /// A covered cell at `block_pos` on an endless board, with mines placed from the seed
+    fn new_endless_cell(&self, block_pos: Vec2) -> Cell {
+        let mut cell = Cell::default();
+        cell.set_mines(self.endless_mines(block_pos));
+        cell.set_adjacent_mines(self.count_adjacent_mines(block_pos));
+        cell
+    }
+
+    /// How many mines the seed puts at `block_pos`, with the same chance of a mine as
+    /// [`Board::gen_mines`] and none at or next to the start
+    fn endless_mines(&self, block_pos: Vec2) -> u32 {
+        // No neighbourhood reaches more than 2 blocks away
+        let offset = block_pos - ENDLESS_START;
+        if offset.x.abs() <= 2
+            && offset.y.abs() <= 2
+            && (block_pos == ENDLESS_START
+                || self
+                    .neighbour_offsets(ENDLESS_START.y)
+                    .iter()
+                    .any(|&offset| ENDLESS_START + offset == block_pos))
+        {
+            return 0;
+        }
+
+        let hash = chunks::block_hash(self.chunks.seed(), block_pos);
+        if hash < u32::MAX >> 3 {
+            1 + hash % self.max_mines_per_block
+        } else {
+            0
+        }
+    }
+
+    fn load_chunk(&mut self, chunk: Vec2) {
+        if self.chunks.is_loaded(chunk) {
+            return;
+        }
+        let cells = core::array::from_fn(|index| self.cell(chunks::block_in(chunk, index)));
+
+        // A chunk forgotten to make room comes back covered, so it can't be cleared for points twice
+        self.cleared_blocks -= self.chunks.load(chunk, cells);
+    }
+
+    /// Load the chunks of an endless board around `block_pos` as the player gets near them,
+    /// packing away the ones they've left behind
+    pub fn explore(&mut self, block_pos: Vec2) {
+        let chunk = chunks::chunk_of(block_pos).0;
+        if self.topology != Topology::Endless || self.chunks.focus() == Some(chunk) {
+            return;
+        }
+
+        self.chunks.set_focus(chunk);
+        for y in -1..=1 {
+            for x in -1..=1 {
+                self.load_chunk(chunk + Vec2::new(x, y));
+            }
+        }
+    }
+
+    /// Every block of the loaded chunks of an endless board
+    fn loaded_blocks(&self) -> Vec<Vec2> {
+        self.chunks
+            .loaded()
+            .flat_map(|chunk| (0..CHUNK_SIZE * CHUNK_SIZE).map(move |index| (chunk, index)))
+            .map(|(chunk, index)| chunks::block_in(chunk, index as usize))
+            .collect()
+    }
+
+    /// Every block on the board on every floor, or only the loaded ones on an endless board
+    fn all_blocks(&self) -> Vec<Vec2> {
+        if self.topology == Topology::Endless {
+            return self.loaded_blocks();
+        }
+        let size = self.size;
+        (0..size.y * self.floors)
+            .flat_map(|y| (0..size.x).map(move |x| Vec2::new(x, y)))
+            .collect()
+    }
+
+    pub fn block(&self, block_pos: Vec2) -> MinefieldBlock {
+        self.cell(block_pos).block()
+    }
+
+    pub fn is_mine(&self, block_pos: Vec2) -> bool {
+        self.mine_count(block_pos) > 0
+    }
+
+    pub fn mine_count(&self, block_pos: Vec2) -> u32 {
+        // Worked out from the seed rather than the cell, which may not have been made yet
+        if self.topology == Topology::Endless {
+            return self.endless_mines(block_pos);
+        }
+        self.cell(block_pos).mines()
+    }
+
+    /// Place or remove a single mine, for boards which aren't generated randomly
+    pub fn set_mine(&mut self, block_pos: Vec2, is_mine: bool) -> &mut Self {
+        self.set_mines(block_pos, is_mine as u32)
+    }
+
+    /// Put exactly `mines` mines in a block, for boards which aren't generated randomly
+    ///
+    /// The mines of an endless board all come from its seed, so they can't be placed by hand, and
+    /// holes are left out of the game so they can't hold any.
+    pub fn set_mines(&mut self, block_pos: Vec2, mines: u32) -> &mut Self {
+        debug_assert_ne!(self.topology, Topology::Endless);
+        let index = self.block_pos_to_index(block_pos);
+        let cell = self.cells[index];
+        if cell.mines() == mines {
+            return self;
+        }
+        debug_assert!(cell.is_playable(), "{block_pos:?} is a hole");
+        self.cells[index].set_mines(mines);
+        self.mine_total = self.mine_total + mines - cell.mines();
+        let is_mine = mines > 0;
+
+        // Keep the counts around the mine and the blocks left to clear up to date
+        for i in 0..self.neighbour_count(block_pos) {
+            let Some(neighbour) = self.neighbour(block_pos, i) else {
+                continue;
+            };
+            let neighbour_index = self.block_pos_to_index(neighbour);
+            let neighbour_cell = &mut self.cells[neighbour_index];
+            neighbour_cell
+                .set_adjacent_mines(neighbour_cell.adjacent_mines() + mines - cell.mines());
+        }
+        if cell.block() != MinefieldBlock::Clear && cell.is_mine() != is_mine {
+            if is_mine {
+                self.covered_safe_blocks -= 1;
+            } else {
+                self.covered_safe_blocks += 1;
+            }
+        }
+        self
+    }
+
+    pub fn flag_count(&self) -> u32 {
+        self.flag_count
+    }
+
+    /// How many mines there are to flag, or `None` on an endless board which never runs out
+    pub fn mine_total(&self) -> Option<u32> {
+        (self.topology != Topology::Endless).then_some(self.mine_total)
+    }
+
+    /// The mine total for row `y`, shown beside it when playing with line clues
+    pub fn row_clue(&self, y: i32) -> LineClue {
+        self.line_clue((0..self.size.x).map(|x| Vec2::new(x, y)))
+    }
+
+    /// The mine total for column `x`, shown above it when playing with line clues
+    pub fn column_clue(&self, x: i32) -> LineClue {
+        self.line_clue((0..self.size.y).map(|y| Vec2::new(x, y)))
+    }
+
+    fn line_clue(&self, line: impl Iterator<Item = Vec2>) -> LineClue {
+        let mut clue = LineClue::default();
+        for block_pos in line.filter(|&block_pos| self.is_playable(block_pos)) {
+            clue.blocks += 1;
+            clue.mines += self.mine_count(block_pos);
+            if let MinefieldBlock::Flag(flags) = self.block(block_pos) {
+                clue.flags += flags;
+            }
+        }
+        clue
+    }
+
+    fn set_block_state(&mut self, block_pos: Vec2, block: MinefieldBlock) {
+        let cell = self.cell(block_pos);
+        if cell.block() == block {
+            return;
+        }
+
+        // Keep the flag count in step with the blocks
+        if let MinefieldBlock::Flag(flags) = cell.block() {
+            self.flag_count -= flags;
+        }
+        if let MinefieldBlock::Flag(flags) = block {
+            self.flag_count += flags;
+        }
+
+        // Along with how many safe blocks are left to clear
+        if !cell.is_mine() {
+            if cell.block() == MinefieldBlock::Clear {
+                self.covered_safe_blocks += 1;
+                self.cleared_blocks -= 1;
+            }
+            if block == MinefieldBlock::Clear {
+                self.covered_safe_blocks -= 1;
+                self.cleared_blocks += 1;
+            }
+        }
+        self.cell_mut(block_pos).set_block(block);
+    }
+
+    pub fn determine_minefield_item(&self, block_pos: Vec2) -> MinefieldItem {
+        self.cell(block_pos).item()
+    }
+
+    /// Clear a block, returning what was underneath it if it was cleared
+    pub fn remove_block(&mut self, block_pos: Vec2, force_remove: bool) -> Option<MinefieldItem> {
+        // Early exit if the block position isn't a block on the board
+        if !self.is_playable(block_pos) {
+            return None;
+        }
+
+        // Check if the block can be cleared (i.e. not cleared or flagged status)
+        if !force_remove {
+            match self.block(block_pos) {
+                MinefieldBlock::Clear | MinefieldBlock::Flag(_) => return None,
+                _ => (),
+            }
+        }
+
+        self.set_block_state(block_pos, MinefieldBlock::Clear);
+        Some(self.determine_minefield_item(block_pos))
+    }
+
+    /// Cycle a covered block through its states, returning the new state if it changed
+    pub fn cycle_block_state(&mut self, block_pos: Vec2) -> Option<MinefieldBlock> {
+        // Early exit if the block position isn't a block on the board
+        if !self.is_playable(block_pos) {
+            return None;
+        }
+
+        // Check if the block can be modified (i.e. not cleared)
+        let next_block_type = match self.block(block_pos) {
+            MinefieldBlock::Clear => return None,
+            MinefieldBlock::Block => MinefieldBlock::Flag(1),
+            MinefieldBlock::Flag(flags) if flags < self.max_mines_per_block => {
+                MinefieldBlock::Flag(flags + 1)
+            }
+            MinefieldBlock::Flag(_) if self.question_marks => MinefieldBlock::Question,
+            MinefieldBlock::Flag(_) | MinefieldBlock::Question => MinefieldBlock::Block,
+        };
+
+        self.set_block_state(block_pos, next_block_type);
+        Some(next_block_type)
+    }
+
+    /// Flag or unflag a covered block, returning the new state if it changed
+    pub fn toggle_flag(&mut self, block_pos: Vec2) -> Option<MinefieldBlock> {
+        if !self.is_playable(block_pos) {
+            return None;
+        }
+
+        let next_block_type = match self.block(block_pos) {
+            MinefieldBlock::Clear => return None,
+            MinefieldBlock::Flag(flags) if flags < self.max_mines_per_block => {
+                MinefieldBlock::Flag(flags + 1)
+            }
+            MinefieldBlock::Flag(_) => MinefieldBlock::Block,
+            MinefieldBlock::Block | MinefieldBlock::Question => MinefieldBlock::Flag(1),
+        };
+
+        self.set_block_state(block_pos, next_block_type);
+        Some(next_block_type)
+    }
+
+    pub fn is_win_condition(&self) -> bool {
+        // win condition is all non-mine blocks are cleared
+        self.covered_safe_blocks == 0
+    }
+
+    pub fn get_surrounding_uncleared_blocks(&self, block_pos: Vec2) -> Vec<Vec2> {
+        // Don't return blocks which have already been cleared
+        self.neighbours(block_pos)
+            .filter(|&block| self.block(block) != MinefieldBlock::Clear)
+            .collect()
+    }
+
+    /// Clear a block chosen by the player, starting a cascade if it turns out to be blank
+    pub fn reveal_block(&mut self, block_pos: Vec2) -> MinefieldState {
+        let Some(minefield_item) = self.remove_block(block_pos, false) else {
+            return MinefieldState::Play;
+        };
+
+        // Go to a game over screen, unless there's a life to spare for defusing the mine
+        if let MinefieldItem::Mine(mines) = minefield_item {
+            if self.lives_per_game.is_some() {
+                self.lives = self.lives.saturating_sub(1);
+                if self.lives > 0 {
+                    self.set_block_state(block_pos, MinefieldBlock::Flag(mines));
+                    return MinefieldState::Play;
+                }
+            }
+            return MinefieldState::GameOver(false);
+        }
+
+        // Go to a win screen
+        if self.is_win_condition() {
+            return MinefieldState::GameOver(true);
+        }
+
+        if minefield_item == MinefieldItem::Blank {
+            self.queue_surrounding_blocks(block_pos);
+        }
+
+        MinefieldState::Play
+    }
+
+    /// The blocks which chording on `block_pos` would reveal, which is none unless it is a
+    /// cleared number with as many flags around it
+    pub fn chord_blocks(&self, block_pos: Vec2) -> Vec<Vec2> {
+        if !self.is_playable(block_pos) || self.block(block_pos) != MinefieldBlock::Clear {
+            return vec![];
+        }
+        let MinefieldItem::Number(mine_count) = self.determine_minefield_item(block_pos) else {
+            return vec![];
+        };
+
+        // Only reveal the surrounding blocks if the player has flagged enough of them
+        let mut surrounding_blocks = self.get_surrounding_uncleared_blocks(block_pos);
+        let surrounding_flag_count: u32 = surrounding_blocks
+            .iter()
+            .map(|&block| match self.block(block) {
+                MinefieldBlock::Flag(flags) => flags,
+                _ => 0,
+            })
+            .sum();
+        if surrounding_flag_count != mine_count {
+            return vec![];
+        }
+
+        surrounding_blocks.retain(|&block| !matches!(self.block(block), MinefieldBlock::Flag(_)));
+        surrounding_blocks
+    }
+
+    pub fn is_cascading(&self) -> bool {
+        self.cascade_layer_start < self.blocks_to_clear.len()
+    }
+
+    fn clear_cascade(&mut self) {
+        for index in 0..self.blocks_to_clear.len() {
+            let block = self.blocks_to_clear[index];
+            self.cell_mut(block).set_queued(false);
+        }
+        self.blocks_to_clear.clear();
+        self.cascade_layer_start = 0;
+    }
+
+    /// Add the uncleared blocks around `block_pos` to the cascade, skipping any already queued
+    fn queue_surrounding_blocks(&mut self, block_pos: Vec2) {
+        for i in 0..self.neighbour_count(block_pos) {
+            let Some(block) = self.neighbour(block_pos, i) else {
+                continue;
+            };
+            if self.block(block) == MinefieldBlock::Clear {
+                continue;
+            }
+
+            // Blank areas of an endless board can go on forever, so a cascade stops at the chunks
+            // around the player and carries on from wherever they reveal next
+            if self.topology == Topology::Endless
+                && !self.chunks.is_near_focus(chunks::chunk_of(block).0)
+            {
+                continue;
+            }
+
+            let cell = self.cell_mut(block);
+            if !cell.is_queued() {
+                cell.set_queued(true);
+                self.blocks_to_clear.push(block);
+            }
+        }
+    }
+
+    /// Clear the next layer of a cascade, calling `on_clear` for each block that was cleared
+    pub fn cascade_step(
+        &mut self,
+        mut on_clear: impl FnMut(Vec2, MinefieldItem),
+    ) -> MinefieldState {
+        // Only clear the blocks queued so far, anything queued while clearing them is the next layer
+        let layer_end = self.blocks_to_clear.len();
+
+        // Remove blocks and queue the surroundings of blocks that come up blank
+        for layer_index in self.cascade_layer_start..layer_end {
+            let block = self.blocks_to_clear[layer_index];
+            let Some(item) = self.remove_block(block, true) else {
+                continue;
+            };
+            on_clear(block, item);
+            if item == MinefieldItem::Blank {
+                self.queue_surrounding_blocks(block);
+            }
+        }
+        self.cascade_layer_start = layer_end;
+
+        if self.is_cascading() {
+            return MinefieldState::Play;
+        }
+        self.clear_cascade();
+
+        // The cascade may have cleared the last of the blocks
+        if self.is_win_condition() {
+            return MinefieldState::GameOver(true);
+        }
+        MinefieldState::Play
+    }
+
+    /// Jump over cleared blocks and holes to the next covered block in `direction`, or to the last
+    /// block before the edge of the board if there isn't one
+    ///
+    /// The jump keeps to the floor of `block_pos`. An endless board has no edge, so the jump stops
+    /// after a chunk's worth of blocks instead.
+    pub fn jump_target(&self, block_pos: Vec2, direction: Vec2) -> Vec2 {
+        let floor_origin = self.floor_origin(self.floor_of(block_pos));
+        let mut target = block_pos;
+        let mut floor_pos = block_pos - floor_origin;
+        let mut crossed_clear_block = false;
+        let mut distance = 0;
+        loop {
+            floor_pos = self.grid.step(floor_pos, direction);
+            distance += 1;
+            if !self.floor_contains(floor_pos)
+                || (self.topology == Topology::Endless && distance > CHUNK_SIZE)
+            {
+                return target;
+            }
+            let next_block = floor_pos + floor_origin;
+            if !self.is_playable(next_block) {
+                crossed_clear_block = true;
+                continue;
+            }
+
+            target = next_block;
+            if self.block(target) == MinefieldBlock::Clear {
+                crossed_clear_block = true;
+            } else if crossed_clear_block {
+                return target;
+            }
+        }
+    }
+
+    /// Clear every block on the board, which is every loaded block on an endless board
+    ///
+    /// Blocks cleared this way don't count towards [`Board::cleared_blocks`].
+    pub fn reveal(&mut self) {
+        let cleared_blocks = self.cleared_blocks;
+        self.clear_cascade();
+        for block_pos in self.all_blocks() {
+            if self.is_playable(block_pos) {
+                self.set_block_state(block_pos, MinefieldBlock::Clear);
+            }
+        }
+        self.cleared_blocks = cleared_blocks;
+    }
+
+    pub fn flag_remaining_mines(&mut self) {
+        for block_pos in self.all_blocks() {
+            let cell = self.cell(block_pos);
+            if cell.is_mine() && cell.block() != MinefieldBlock::Clear {
+                self.set_block_state(block_pos, MinefieldBlock::Flag(cell.mines()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rng;
+
+    #[test]
+    fn counts_surrounding_mines() {
+        let board = Board::with_mines(Vec2::new(3, 3), &[Vec2::new(0, 0), Vec2::new(2, 1)]);
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(1, 1)),
+            MinefieldItem::Number(2)
+        );
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(0, 2)),
+            MinefieldItem::Blank
+        );
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(0, 0)),
+            MinefieldItem::Mine(1)
+        );
+    }
+
+    #[test]
+    fn hex_grid_counts_six_neighbours() {
+        // Odd rows are to the right, so (1, 1) is next to (2, 0) but not (0, 0)
+        let mut board = Board::with_mines(
+            Vec2::new(3, 3),
+            &[Vec2::new(0, 0), Vec2::new(2, 0), Vec2::new(2, 2)],
+        );
+        board.set_grid(Grid::Hex);
+        assert_eq!(board.neighbours(Vec2::new(1, 1)).count(), 6);
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(1, 1)),
+            MinefieldItem::Number(2)
+        );
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(0, 1)),
+            MinefieldItem::Number(1)
+        );
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(2, 1)),
+            MinefieldItem::Number(2)
+        );
+    }
+
+    #[test]
+    fn line_clues_count_mines_and_flags() {
+        let mut board = Board::new(Vec2::new(4, 3));
+        board
+            .set_max_mines_per_block(3)
+            .set_mines(Vec2::new(0, 0), 2)
+            .set_mines(Vec2::new(3, 0), 1)
+            .set_mines(Vec2::new(3, 2), 1);
+        assert_eq!(board.row_clue(0).mines, 3);
+        assert_eq!(board.row_clue(1).mines, 0);
+        assert_eq!(board.column_clue(3).mines, 2);
+        assert_eq!(board.column_clue(3).blocks, 3);
+
+        // Each flag counts, right or wrong, and too many leave less than nothing to find
+        board.toggle_flag(Vec2::new(0, 0));
+        board.toggle_flag(Vec2::new(0, 0));
+        board.toggle_flag(Vec2::new(1, 0));
+        assert_eq!(board.row_clue(0).flags, 3);
+        assert_eq!(board.row_clue(0).remaining(), 0);
+        assert_eq!(board.column_clue(1).remaining(), -1);
+
+        // Holes aren't part of any line
+        board.set_shape(&Shape::new("GAP", &["#.#", "#.#"]));
+        assert_eq!(board.column_clue(1), LineClue::default());
+        assert_eq!(board.row_clue(1).blocks, 2);
+    }
+
+    #[test]
+    fn toroidal_edges_neighbour_each_other() {
+        let mut board = Board::with_mines(Vec2::new(4, 3), &[Vec2::new(3, 2)]);
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(0, 0)),
+            MinefieldItem::Blank
+        );
+
+        // The opposite corner is diagonally next to the top left once the edges join up
+        board.set_topology(Topology::Toroidal);
+        assert_eq!(board.neighbours(Vec2::new(0, 0)).count(), 8);
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(0, 0)),
+            MinefieldItem::Number(1)
+        );
+
+        // A board 2 blocks wide reaches the same blocks going left and right
+        board.set_size(Vec2::new(2, 3));
+        assert_eq!(board.neighbours(Vec2::new(0, 1)).count(), 5);
+
+        // Hex rows alternate, so an odd number of them only joins up at the sides
+        board.set_size(Vec2::new(4, 3)).set_grid(Grid::Hex);
+        assert!(!board.wraps_vertically());
+        assert_eq!(board.neighbours(Vec2::new(1, 0)).count(), 4);
+        assert!(
+            board
+                .neighbours(Vec2::new(0, 2))
+                .all(|neighbour| neighbour.y != 0)
+        );
+        board.set_size(Vec2::new(4, 4));
+        assert_eq!(board.neighbours(Vec2::new(1, 0)).count(), 6);
+    }
+
+    #[test]
+    fn floors_count_the_blocks_above_and_below() {
+        let mut board = Board::new(Vec2::new(3, 3));
+        board.set_floors(3);
+        assert_eq!(board.floor_of(Vec2::new(1, 4)), 1);
+        assert_eq!(board.floor_step(Vec2::new(1, 1), 1), Some(Vec2::new(1, 4)));
+        assert_eq!(board.floor_step(Vec2::new(1, 1), -1), None);
+
+        // The middle block of the middle floor is in a 3x3x3 cube, with the rows of a floor
+        // stopping at its edge rather than running on into the next floor
+        assert_eq!(board.neighbours(Vec2::new(1, 4)).count(), 26);
+        assert_eq!(board.neighbours(Vec2::new(0, 2)).count(), 3 + 4);
+        assert!(!board.neighbours(Vec2::new(0, 2)).any(|block| block.y == 3));
+
+        board.set_mine(Vec2::new(0, 0), true);
+        board.set_mine(Vec2::new(2, 8), true);
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(1, 4)),
+            MinefieldItem::Number(2)
+        );
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(0, 3)),
+            MinefieldItem::Number(1)
+        );
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(0, 6)),
+            MinefieldItem::Blank
+        );
+
+        // A cascade carries on through the floors, clearing everything but the mines
+        assert_eq!(board.reveal_block(Vec2::new(2, 0)), MinefieldState::Play);
+        while board.is_cascading() {
+            board.cascade_step(|_, _| ());
+        }
+        assert_eq!(board.block(Vec2::new(0, 8)), MinefieldBlock::Clear);
+        assert!(board.is_win_condition());
+    }
+
+    #[test]
+    fn casual_play_defuses_mines_until_out_of_lives() {
+        let mines = [Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(2, 0)];
+        let mut board = Board::with_mines(Vec2::new(4, 4), &mines);
+        board.set_lives_per_game(Some(2));
+        assert_eq!(board.lives(), Some(2));
+
+        // The first mine costs a life and is left flagged
+        assert_eq!(board.reveal_block(mines[0]), MinefieldState::Play);
+        assert_eq!(board.lives(), Some(1));
+        assert_eq!(board.block(mines[0]), MinefieldBlock::Flag(1));
+        assert_eq!(board.flag_count(), 1);
+
+        // The last life ends the game like any mine in the standard game
+        assert_eq!(
+            board.reveal_block(mines[1]),
+            MinefieldState::GameOver(false)
+        );
+        assert_eq!(board.lives(), Some(0));
+
+        // A new game has every life back, and the standard game has none to lose
+        board.reset();
+        assert_eq!(board.lives(), Some(2));
+        board.set_lives_per_game(None);
+        assert_eq!(board.lives(), None);
+        assert_eq!(
+            board.reveal_block(mines[2]),
+            MinefieldState::GameOver(false)
+        );
+    }
+
+    #[test]
+    fn neighbourhood_decides_what_is_counted() {
+        let mines = [Vec2::new(0, 0), Vec2::new(2, 1), Vec2::new(4, 4)];
+        let mut board = Board::with_mines(Vec2::new(5, 5), &mines);
+        let centre = Vec2::new(2, 2);
+
+        let counts = [
+            (Neighbourhood::Standard, MinefieldItem::Number(1)),
+            (Neighbourhood::Cross, MinefieldItem::Number(1)),
+            (Neighbourhood::Knight, MinefieldItem::Blank),
+            (Neighbourhood::Radius2, MinefieldItem::Number(3)),
+        ];
+        for (neighbourhood, item) in counts {
+            board.set_neighbourhood(neighbourhood);
+            assert_eq!(
+                board.determine_minefield_item(centre),
+                item,
+                "{neighbourhood:?}"
+            );
+        }
+
+        // A knight's move from the corner mine
+        board.set_neighbourhood(Neighbourhood::Knight);
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(1, 2)),
+            MinefieldItem::Number(1)
+        );
+
+        // Wrapped around a board only as wide as the neighbourhood, the blocks 2 to either side are
+        // the same ones
+        board
+            .set_neighbourhood(Neighbourhood::Radius2)
+            .set_topology(Topology::Toroidal);
+        assert_eq!(board.neighbours(centre).count(), 24);
+        board.set_size(Vec2::new(4, 5));
+        assert_eq!(board.neighbours(centre).count(), 19);
+    }
+
+    #[test]
+    fn blocks_hold_and_flag_several_mines() {
+        let mut board = Board::new(Vec2::new(3, 2));
+        board
+            .set_max_mines_per_block(3)
+            .set_mines(Vec2::new(0, 0), 3)
+            .set_mines(Vec2::new(2, 0), 2);
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(1, 0)),
+            MinefieldItem::Number(5)
+        );
+
+        // Flagging counts up to the most mines a block can hold, then takes the flags off
+        for flags in 1..=3 {
+            assert_eq!(
+                board.toggle_flag(Vec2::new(0, 0)),
+                Some(MinefieldBlock::Flag(flags))
+            );
+        }
+        assert_eq!(board.flag_count(), 3);
+
+        // Chording needs as many flags as the number, not as many flagged blocks
+        board.remove_block(Vec2::new(1, 0), false);
+        assert!(board.chord_blocks(Vec2::new(1, 0)).is_empty());
+        board.toggle_flag(Vec2::new(2, 0));
+        board.toggle_flag(Vec2::new(2, 0));
+        assert_eq!(board.flag_count(), 5);
+        assert_eq!(board.chord_blocks(Vec2::new(1, 0)).len(), 3);
+
+        assert_eq!(
+            board.toggle_flag(Vec2::new(0, 0)),
+            Some(MinefieldBlock::Block)
+        );
+        assert_eq!(board.flag_count(), 2);
+    }
+
+    #[test]
+    fn holes_are_left_out_of_the_game() {
+        // A ring, with the mine on the far side of the hole from the corner
+        let shape = Shape::new("RING", &["####", "#..#", "####"]);
+        let mut board = Board::new(Vec2::new(1, 1));
+        board.set_shape(&shape).set_mine(Vec2::new(3, 2), true);
+        assert!(!board.is_playable(Vec2::new(1, 1)));
+        assert_eq!(board.neighbours(Vec2::new(1, 0)).count(), 3);
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(2, 1)),
+            MinefieldItem::Blank
+        );
+        assert_eq!(board.toggle_flag(Vec2::new(2, 1)), None);
+        assert_eq!(board.remove_block(Vec2::new(2, 1), false), None);
+
+        // The cascade goes around the ring and wins without the holes being cleared
+        board.reveal_block(Vec2::new(0, 0));
+        let mut state = MinefieldState::Play;
+        while board.is_cascading() {
+            state = board.cascade_step(|block_pos, _| assert!(shape.is_playable(block_pos)));
+        }
+        assert_eq!(state, MinefieldState::GameOver(true));
+        assert_eq!(board.block(Vec2::new(1, 1)), MinefieldBlock::Block);
+
+        // Jumping crosses the hole, and the nearest block to it is on the ring
+        board.reset();
+        assert_eq!(
+            board.jump_target(Vec2::new(1, 0), Vec2::new(0, 1)),
+            Vec2::new(1, 2)
+        );
+        assert_eq!(board.nearest_playable(Vec2::new(1, 1)), Vec2::new(1, 0));
+    }
+
+    #[test]
+    fn endless_boards_are_made_as_they_are_explored() {
+        let mut board = Board::new(Vec2::new(1, 1));
+        board.set_topology(Topology::Endless);
+        let mut seed = 7;
+        board.gen_mines(|| {
+            seed += 1;
+            seed
+        });
+
+        // The start is cleared for free, and the board carries on past where it began
+        assert_eq!(board.block(ENDLESS_START), MinefieldBlock::Clear);
+        assert_eq!(board.cleared_blocks(), 0);
+        assert!(board.contains(Vec2::new(-500, 300)));
+
+        // Numbers far from the start still count the mines around them
+        let far = Vec2::new(-20 * CHUNK_SIZE, 7 * CHUNK_SIZE);
+        board.explore(far);
+        let safe: Vec<Vec2> = (0..CHUNK_SIZE)
+            .map(|x| far + Vec2::new(x, 0))
+            .filter(|&block_pos| !board.is_mine(block_pos))
+            .take(2)
+            .collect();
+        let mine_count = board
+            .neighbours(safe[0])
+            .map(|neighbour| board.mine_count(neighbour))
+            .sum();
+        assert_eq!(
+            board.determine_minefield_item(safe[0]),
+            match mine_count {
+                0 => MinefieldItem::Blank,
+                n => MinefieldItem::Number(n),
+            }
+        );
+        board.toggle_flag(safe[0]);
+        board.reveal_block(safe[1]);
+        while board.is_cascading() {
+            board.cascade_step(|_, _| ());
+        }
+        let cleared = board.cleared_blocks();
+        assert!(cleared > 0);
+
+        // Wandering off packs the chunk away, and coming back finds it as it was left
+        for step in 1..=4 {
+            board.explore(far + Vec2::new(0, step * 3 * CHUNK_SIZE));
+        }
+        assert!(!board.chunks.is_loaded(chunks::chunk_of(far).0));
+        assert_eq!(board.block(safe[0]), MinefieldBlock::Flag(1));
+        board.explore(far);
+        assert_eq!(board.block(safe[0]), MinefieldBlock::Flag(1));
+        assert_eq!(board.block(safe[1]), MinefieldBlock::Clear);
+        assert_eq!(board.cleared_blocks(), cleared);
+
+        // The game never runs out of blocks to win with, and giving up doesn't add to the score
+        board.reveal();
+        assert!(!board.is_win_condition());
+        assert_eq!(board.cleared_blocks(), cleared);
+    }
+
+    #[test]
+    fn forgotten_chunks_are_taken_off_the_score() {
+        let mut board = Board::new(Vec2::new(1, 1));
+        board.set_topology(Topology::Endless);
+        let mut rng = Rng::new(5);
+        board.gen_mines(|| rng.next_i32());
+
+        // Clear a block in each chunk along a walk long enough for the first ones to be forgotten
+        let first_chunk = Vec2::new(10, 0);
+        let walk = 100;
+        let mut first_cleared = None;
+        for step in 0..walk {
+            let chunk = first_chunk + Vec2::new(step, 0);
+            board.explore(chunks::block_in(chunk, 0));
+            let covered_safe = (0..CHUNK_SIZE * CHUNK_SIZE)
+                .map(|index| chunks::block_in(chunk, index as usize))
+                .find(|&block_pos| {
+                    !board.is_mine(block_pos) && board.block(block_pos) == MinefieldBlock::Block
+                });
+            if let Some(block_pos) = covered_safe {
+                board.reveal_block(block_pos);
+                while board.is_cascading() {
+                    board.cascade_step(|_, _| ());
+                }
+                first_cleared.get_or_insert(block_pos);
+            }
+        }
+
+        // The score only counts the cleared blocks which are still remembered
+        let remembered_score = |board: &Board| -> u32 {
+            (-1..=walk)
+                .flat_map(|x| (-1..=1).map(move |y| first_chunk + Vec2::new(x, y)))
+                .flat_map(|chunk| {
+                    (0..CHUNK_SIZE * CHUNK_SIZE)
+                        .map(move |index| chunks::block_in(chunk, index as usize))
+                })
+                .filter(|&block_pos| {
+                    !board.is_mine(block_pos) && board.block(block_pos) == MinefieldBlock::Clear
+                })
+                .count() as u32
+        };
+        let first_cleared = first_cleared.unwrap();
+        assert_eq!(board.block(first_cleared), MinefieldBlock::Block);
+        assert_eq!(board.cleared_blocks(), remembered_score(&board));
+
+        // So going back to clear it again doesn't add to the score twice
+        board.explore(first_cleared);
+        board.reveal_block(first_cleared);
+        while board.is_cascading() {
+            board.cascade_step(|_, _| ());
+        }
+        assert_eq!(board.cleared_blocks(), remembered_score(&board));
+    }
+
+    #[test]
+    fn cascade_clears_blank_area_and_wins() {
+        let mut board = Board::with_mines(Vec2::new(4, 4), &[Vec2::new(3, 3)]);
+        assert_eq!(board.reveal_block(Vec2::new(0, 0)), MinefieldState::Play);
+
+        let mut state = MinefieldState::Play;
+        while board.is_cascading() {
+            state = board.cascade_step(|_, item| assert!(!matches!(item, MinefieldItem::Mine(_))));
+        }
+        assert_eq!(state, MinefieldState::GameOver(true));
+        assert_eq!(board.block(Vec2::new(3, 3)), MinefieldBlock::Block);
+    }
+
+    #[test]
+    fn cascade_clears_each_block_once() {
+        let size = Vec2::new(30, 20);
+        let mut board = Board::new(size);
+        let capacity = board.blocks_to_clear.capacity();
+        board.reveal_block(Vec2::new(15, 10));
+
+        let mut clear_count = 0;
+        while board.is_cascading() {
+            assert!(board.blocks_to_clear.len() <= (size.x * size.y) as usize);
+            board.cascade_step(|_, _| clear_count += 1);
+        }
+        assert_eq!(clear_count, size.x * size.y - 1);
+        assert_eq!(board.blocks_to_clear.capacity(), capacity);
+    }
+
+    #[test]
+    fn moving_a_mine_updates_counts_and_win() {
+        let mut board = Board::with_mines(Vec2::new(3, 1), &[Vec2::new(0, 0)]);
+        board.remove_block(Vec2::new(1, 0), false);
+        board.remove_block(Vec2::new(2, 0), false);
+        assert!(board.is_win_condition());
+
+        board
+            .set_mine(Vec2::new(0, 0), false)
+            .set_mine(Vec2::new(2, 0), true);
+        assert_eq!(
+            board.determine_minefield_item(Vec2::new(1, 0)),
+            MinefieldItem::Number(1)
+        );
+        assert!(!board.is_win_condition());
+        assert_eq!(
+            board.reveal_block(Vec2::new(0, 0)),
+            MinefieldState::GameOver(true)
+        );
+    }
+
+    #[test]
+    fn flag_count_follows_block_states() {
+        let mut board = Board::with_mines(Vec2::new(2, 2), &[Vec2::new(0, 0)]);
+        board.set_question_marks(false);
+        assert_eq!(
+            board.cycle_block_state(Vec2::new(1, 1)),
+            Some(MinefieldBlock::Flag(1))
+        );
+        assert_eq!(
+            board.toggle_flag(Vec2::new(0, 1)),
+            Some(MinefieldBlock::Flag(1))
+        );
+        assert_eq!(board.flag_count(), 2);
+
+        assert_eq!(
+            board.cycle_block_state(Vec2::new(1, 1)),
+            Some(MinefieldBlock::Block)
+        );
+        assert_eq!(board.flag_count(), 1);
+
+        board.reveal();
+        assert_eq!(board.flag_count(), 0);
+    }
+
+    #[test]
+    fn mine_total_follows_the_mines() {
+        let mut board = Board::with_mines(Vec2::new(3, 3), &[Vec2::new(0, 0), Vec2::new(1, 0)]);
+        assert_eq!(board.mine_total(), Some(2));
+        board
+            .set_max_mines_per_block(3)
+            .set_mines(Vec2::new(0, 0), 3);
+        assert_eq!(board.mine_total(), Some(4));
+
+        // Generated mines are counted as they're placed
+        let mut rng = Rng::new(1);
+        board.gen_mines(|| rng.next_i32());
+        let mines = board
+            .all_blocks()
+            .into_iter()
+            .map(|block_pos| board.mine_count(block_pos));
+        assert_eq!(board.mine_total(), Some(mines.sum()));
+
+        board.set_size(Vec2::new(2, 2));
+        assert_eq!(board.mine_total(), Some(0));
+        board.set_topology(Topology::Endless);
+        assert_eq!(board.mine_total(), None);
+    }
+}
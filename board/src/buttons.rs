@@ -0,0 +1,103 @@
+use core::ops::{BitOr, BitOrAssign};
+
+use crate::Vec2;
+
+/// A set of buttons, using the same bit layout as the GBA's key input register
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Buttons(u16);
+
+impl Buttons {
+    pub const NONE: Self = Self(0);
+    pub const A: Self = Self(1 << 0);
+    pub const B: Self = Self(1 << 1);
+    pub const SELECT: Self = Self(1 << 2);
+    pub const START: Self = Self(1 << 3);
+    pub const RIGHT: Self = Self(1 << 4);
+    pub const LEFT: Self = Self(1 << 5);
+    pub const UP: Self = Self(1 << 6);
+    pub const DOWN: Self = Self(1 << 7);
+    pub const R: Self = Self(1 << 8);
+    pub const L: Self = Self(1 << 9);
+
+    // Every bit which is a button
+    const ALL: u16 = (1 << 10) - 1;
+
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Create a set of buttons from raw bits, ignoring any bits which aren't buttons
+    pub const fn from_bits_truncate(bits: u16) -> Self {
+        Self(bits & Self::ALL)
+    }
+
+    /// Whether any of the buttons in `other` are also in this set
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for Buttons {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Buttons {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+/// The buttons held this frame and last frame, which is everything needed to tell presses and
+/// releases apart
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Input {
+    previous: Buttons,
+    current: Buttons,
+}
+
+impl Input {
+    pub fn new(previous: Buttons, current: Buttons) -> Self {
+        Self { previous, current }
+    }
+
+    /// Move on to the next frame, where `current` is held
+    pub fn update(&mut self, current: Buttons) {
+        self.previous = self.current;
+        self.current = current;
+    }
+
+    pub fn is_pressed(&self, buttons: Buttons) -> bool {
+        self.current.intersects(buttons)
+    }
+
+    pub fn is_just_pressed(&self, buttons: Buttons) -> bool {
+        self.current.intersects(buttons) && !self.previous.intersects(buttons)
+    }
+
+    pub fn is_just_released(&self, buttons: Buttons) -> bool {
+        !self.current.intersects(buttons) && self.previous.intersects(buttons)
+    }
+
+    /// The direction held on the D-pad
+    pub fn vector(&self) -> Vec2 {
+        direction(|button| self.is_pressed(button))
+    }
+
+    /// The direction just pressed on the D-pad
+    pub fn just_pressed_vector(&self) -> Vec2 {
+        direction(|button| self.is_just_pressed(button))
+    }
+}
+
+fn direction(is_pressed: impl Fn(Buttons) -> bool) -> Vec2 {
+    // Opposite directions cancel each other out
+    let axis = |negative, positive| is_pressed(positive) as i32 - is_pressed(negative) as i32;
+    Vec2::new(
+        axis(Buttons::LEFT, Buttons::RIGHT),
+        axis(Buttons::UP, Buttons::DOWN),
+    )
+}
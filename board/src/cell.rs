@@ -0,0 +1,101 @@
+use crate::{
+    MinefieldBlock, MinefieldItem, board::MAX_MINES_PER_BLOCK, neighbourhood::MAX_NEIGHBOURS,
+};
+
+/// Everything the board knows about a single block, packed into 16 bits
+///
+/// Bit 14 is set for a hole in a shaped board, bits 12-13 count the mines in the block, bit 11 is
+/// set while the block is queued in a cascade, bits 8-10 hold what's covering the block and bits 0-7
+/// count the mines around it.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub(crate) struct Cell(u16);
+
+impl Cell {
+    const HOLE: u16 = 0b100_0000_0000_0000;
+    const MINES_MASK: u16 = 0b011_0000_0000_0000;
+    const MINES_SHIFT: u16 = 12;
+    const QUEUED: u16 = 0b000_1000_0000_0000;
+    const BLOCK_MASK: u16 = 0b000_0111_0000_0000;
+    const BLOCK_SHIFT: u16 = 8;
+    const ADJACENT_MINES_MASK: u16 = 0b000_0000_1111_1111;
+
+    /// Whether the block is part of the board, rather than a hole in its shape
+    pub fn is_playable(self) -> bool {
+        self.0 & Self::HOLE == 0
+    }
+
+    pub fn set_playable(&mut self, playable: bool) {
+        self.set_bit(Self::HOLE, !playable);
+    }
+
+    pub fn is_mine(self) -> bool {
+        self.mines() > 0
+    }
+
+    pub fn mines(self) -> u32 {
+        ((self.0 & Self::MINES_MASK) >> Self::MINES_SHIFT) as u32
+    }
+
+    pub fn set_mines(&mut self, mines: u32) {
+        debug_assert!(mines <= MAX_MINES_PER_BLOCK);
+        self.0 = (self.0 & !Self::MINES_MASK) | (mines as u16) << Self::MINES_SHIFT;
+    }
+
+    pub fn is_queued(self) -> bool {
+        self.0 & Self::QUEUED != 0
+    }
+
+    pub fn set_queued(&mut self, queued: bool) {
+        self.set_bit(Self::QUEUED, queued);
+    }
+
+    pub fn block(self) -> MinefieldBlock {
+        match (self.0 & Self::BLOCK_MASK) >> Self::BLOCK_SHIFT {
+            0 => MinefieldBlock::Block,
+            1 => MinefieldBlock::Question,
+            2 => MinefieldBlock::Clear,
+            flags => MinefieldBlock::Flag(flags as u32 - 2),
+        }
+    }
+
+    pub fn set_block(&mut self, block: MinefieldBlock) {
+        let bits = match block {
+            MinefieldBlock::Block => 0,
+            MinefieldBlock::Question => 1,
+            MinefieldBlock::Clear => 2,
+            MinefieldBlock::Flag(flags) => {
+                debug_assert!((1..=MAX_MINES_PER_BLOCK).contains(&flags));
+                2 + flags as u16
+            }
+        };
+        self.0 = (self.0 & !Self::BLOCK_MASK) | (bits << Self::BLOCK_SHIFT);
+    }
+
+    pub fn adjacent_mines(self) -> u32 {
+        (self.0 & Self::ADJACENT_MINES_MASK) as u32
+    }
+
+    pub fn set_adjacent_mines(&mut self, adjacent_mines: u32) {
+        debug_assert!(adjacent_mines <= MAX_NEIGHBOURS as u32 * MAX_MINES_PER_BLOCK);
+        self.0 = (self.0 & !Self::ADJACENT_MINES_MASK) | adjacent_mines as u16;
+    }
+
+    /// What the player would find if they cleared this block
+    pub fn item(self) -> MinefieldItem {
+        if self.is_mine() {
+            MinefieldItem::Mine(self.mines())
+        } else if self.adjacent_mines() > 0 {
+            MinefieldItem::Number(self.adjacent_mines())
+        } else {
+            MinefieldItem::Blank
+        }
+    }
+
+    fn set_bit(&mut self, bit: u16, set: bool) {
+        if set {
+            self.0 |= bit;
+        } else {
+            self.0 &= !bit;
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use alloc::vec::Vec;
+
+use crate::{MinefieldBlock, Vec2, board::ENDLESS_START, cell::Cell};
+
+/// Blocks along each side of a chunk of an endless board
+pub(crate) const CHUNK_SIZE: i32 = 16;
+const CHUNK_BLOCKS: usize = (CHUNK_SIZE * CHUNK_SIZE) as usize;
+
+// Chunks kept ready to play on, which is the 3x3 around the cursor with room for a cascade
+const MAX_LOADED_CHUNKS: usize = 16;
+
+// Chunks remembered once the cursor has moved away from them, after which the furthest away are
+// forgotten and come back covered, apart from the ones around the start which hold the free
+// opening
+const MAX_PACKED_CHUNKS: usize = 128;
+
+// What's covering each block of a packed chunk takes 3 bits, kept as one bit in each of 3 planes
+const PLANES: usize = 3;
+const PLANE_WORDS: usize = CHUNK_BLOCKS / 64;
+
+/// The chunk holding `block_pos`, and the index of the block within it
+pub(crate) fn chunk_of(block_pos: Vec2) -> (Vec2, usize) {
+    let chunk = Vec2::new(
+        block_pos.x.div_euclid(CHUNK_SIZE),
+        block_pos.y.div_euclid(CHUNK_SIZE),
+    );
+    let index =
+        block_pos.x.rem_euclid(CHUNK_SIZE) + block_pos.y.rem_euclid(CHUNK_SIZE) * CHUNK_SIZE;
+    (chunk, index as usize)
+}
+
+/// The block at `index` within `chunk`
+pub(crate) fn block_in(chunk: Vec2, index: usize) -> Vec2 {
+    let index = index as i32;
+    chunk * CHUNK_SIZE + Vec2::new(index % CHUNK_SIZE, index / CHUNK_SIZE)
+}
+
+/// A number picked at random for `block_pos` from `seed`, the same every time it's asked for
+pub(crate) fn block_hash(seed: u32, block_pos: Vec2) -> u32 {
+    let mut hash = seed
+        ^ (block_pos.x as u32).wrapping_mul(0x9e37_79b1)
+        ^ (block_pos.y as u32).wrapping_mul(0x85eb_ca77);
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x7feb_352d);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x846c_a68b);
+    hash ^ (hash >> 16)
+}
+
+/// Only what's covering the blocks of a chunk, since the mines can be worked out again from the
+/// seed
+#[derive(Clone)]
+struct PackedChunk {
+    pos: Vec2,
+    planes: [[u64; PLANE_WORDS]; PLANES],
+    cleared: u32, // safe blocks cleared, which stop counting towards the score if it's forgotten
+}
+
+impl PackedChunk {
+    fn new(pos: Vec2, cells: &[Cell; CHUNK_BLOCKS]) -> Self {
+        let mut planes = [[0; PLANE_WORDS]; PLANES];
+        for (index, cell) in cells.iter().enumerate() {
+            let code = match cell.block() {
+                MinefieldBlock::Block => 0,
+                MinefieldBlock::Question => 1,
+                MinefieldBlock::Clear => 2,
+                MinefieldBlock::Flag(flags) => 2 + flags,
+            };
+            for (plane, words) in planes.iter_mut().enumerate() {
+                words[index / 64] |= (((code >> plane) & 1) as u64) << (index % 64);
+            }
+        }
+        let cleared = cells
+            .iter()
+            .filter(|cell| cell.block() == MinefieldBlock::Clear && !cell.is_mine())
+            .count() as u32;
+        Self {
+            pos,
+            planes,
+            cleared,
+        }
+    }
+
+    fn block(&self, index: usize) -> MinefieldBlock {
+        let code = (0..PLANES).fold(0, |code, plane| {
+            code | (((self.planes[plane][index / 64] >> (index % 64)) & 1) as u32) << plane
+        });
+        match code {
+            0 => MinefieldBlock::Block,
+            1 => MinefieldBlock::Question,
+            2 => MinefieldBlock::Clear,
+            flags => MinefieldBlock::Flag(flags - 2),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct LoadedChunk {
+    pos: Vec2,
+    cells: [Cell; CHUNK_BLOCKS],
+}
+
+/// The blocks of an endless board, kept a chunk at a time around where the player is
+///
+/// Chunks are made as they're needed, with mines placed from the seed so that any chunk can be
+/// made again exactly. Once there are too many of them, the ones furthest from the focus are
+/// packed down to what's covering their blocks, or dropped altogether if nothing has been done to
+/// them. Packed chunks are forgotten in turn once there are too many of those, taking the blocks
+/// cleared in them off the score since they come back covered.
+#[derive(Clone, Default)]
+pub(crate) struct Chunks {
+    seed: u32,
+    focus: Option<Vec2>, // the chunk the player is in
+    loaded: Vec<LoadedChunk>,
+    packed: Vec<PackedChunk>,
+}
+
+impl Chunks {
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Forget every chunk, making them again from `seed` from now on
+    pub fn clear(&mut self, seed: u32) {
+        self.seed = seed;
+        self.focus = None;
+        self.loaded.clear();
+        self.packed.clear();
+    }
+
+    pub fn focus(&self) -> Option<Vec2> {
+        self.focus
+    }
+
+    pub fn set_focus(&mut self, chunk: Vec2) {
+        self.focus = Some(chunk);
+    }
+
+    /// Whether `chunk` is the focus or one of the 8 around it
+    pub fn is_near_focus(&self, chunk: Vec2) -> bool {
+        self.focus.is_some_and(|focus| {
+            let offset = chunk - focus;
+            offset.x.abs() <= 1 && offset.y.abs() <= 1
+        })
+    }
+
+    pub fn is_loaded(&self, chunk: Vec2) -> bool {
+        self.loaded.iter().any(|loaded| loaded.pos == chunk)
+    }
+
+    /// Chunks which are ready to play on
+    pub fn loaded(&self) -> impl Iterator<Item = Vec2> + '_ {
+        self.loaded.iter().map(|loaded| loaded.pos)
+    }
+
+    pub fn cell(&self, block_pos: Vec2) -> Option<Cell> {
+        let (chunk, index) = chunk_of(block_pos);
+        let loaded = self.loaded.iter().find(|loaded| loaded.pos == chunk)?;
+        Some(loaded.cells[index])
+    }
+
+    pub fn cell_mut(&mut self, block_pos: Vec2) -> Option<&mut Cell> {
+        let (chunk, index) = chunk_of(block_pos);
+        let loaded = self.loaded.iter_mut().find(|loaded| loaded.pos == chunk)?;
+        Some(&mut loaded.cells[index])
+    }
+
+    /// What was covering `block_pos` when its chunk was packed away, or a plain block if it never
+    /// has been
+    pub fn packed_block(&self, block_pos: Vec2) -> MinefieldBlock {
+        let (chunk, index) = chunk_of(block_pos);
+        self.packed
+            .iter()
+            .find(|packed| packed.pos == chunk)
+            .map_or(MinefieldBlock::Block, |packed| packed.block(index))
+    }
+
+    /// Add a newly made chunk, packing away the loaded chunk furthest from the focus if there are
+    /// too many, and return how many cleared blocks were forgotten to make room for it
+    pub fn load(&mut self, chunk: Vec2, cells: [Cell; CHUNK_BLOCKS]) -> u32 {
+        self.packed.retain(|packed| packed.pos != chunk);
+        let mut forgotten = 0;
+        if self.loaded.len() == MAX_LOADED_CHUNKS {
+            let furthest = self.furthest(self.loaded.iter().map(|loaded| loaded.pos).enumerate());
+            let unloaded = self.loaded.swap_remove(furthest);
+            forgotten = self.pack(unloaded);
+        }
+        self.loaded.push(LoadedChunk { pos: chunk, cells });
+        forgotten
+    }
+
+    fn pack(&mut self, chunk: LoadedChunk) -> u32 {
+        // An untouched chunk comes back the same from the seed alone
+        if chunk
+            .cells
+            .iter()
+            .all(|cell| cell.block() == MinefieldBlock::Block)
+        {
+            return 0;
+        }
+
+        // The opening was cleared for free, so the chunks holding it are never forgotten, leaving
+        // every other cleared block to have been counted towards the score
+        let mut forgotten = 0;
+        if self.packed.len() == MAX_PACKED_CHUNKS {
+            let start = chunk_of(ENDLESS_START).0;
+            let furthest = self.furthest(
+                self.packed
+                    .iter()
+                    .map(|packed| packed.pos)
+                    .enumerate()
+                    .filter(|&(_, chunk)| {
+                        let offset = chunk - start;
+                        offset.x.abs() > 1 || offset.y.abs() > 1
+                    }),
+            );
+            forgotten = self.packed.swap_remove(furthest).cleared;
+        }
+        self.packed.push(PackedChunk::new(chunk.pos, &chunk.cells));
+        forgotten
+    }
+
+    /// Index of the chunk furthest from the focus, out of `chunks` given along with their indices
+    fn furthest(&self, chunks: impl Iterator<Item = (usize, Vec2)>) -> usize {
+        let focus = self.focus.unwrap_or_default();
+        chunks
+            .max_by_key(|&(_, chunk)| {
+                let offset = chunk - focus;
+                offset.x.abs().max(offset.y.abs())
+            })
+            .map_or(0, |(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packing_keeps_what_covers_each_block() {
+        let blocks = [
+            MinefieldBlock::Block,
+            MinefieldBlock::Question,
+            MinefieldBlock::Clear,
+            MinefieldBlock::Flag(1),
+            MinefieldBlock::Flag(3),
+        ];
+        let mut cells = [Cell::default(); CHUNK_BLOCKS];
+        for (index, cell) in cells.iter_mut().enumerate() {
+            cell.set_block(blocks[index % blocks.len()]);
+        }
+
+        let packed = PackedChunk::new(Vec2::new(-3, 2), &cells);
+        for (index, cell) in cells.iter().enumerate() {
+            assert_eq!(packed.block(index), cell.block(), "block {index}");
+        }
+    }
+
+    #[test]
+    fn chunks_cover_negative_blocks() {
+        let (chunk, index) = chunk_of(Vec2::new(-1, -CHUNK_SIZE));
+        assert_eq!(chunk, Vec2::new(-1, -1));
+        assert_eq!(block_in(chunk, index), Vec2::new(-1, -CHUNK_SIZE));
+    }
+}
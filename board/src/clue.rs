@@ -0,0 +1,14 @@
+/// The mine total for a row or column of the board, with how many flags the player has put on it
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct LineClue {
+    pub mines: u32,
+    pub flags: u32,
+    pub blocks: u32, // playable blocks in the line, since a line of holes has nothing to count
+}
+
+impl LineClue {
+    /// Mines in the line which haven't been flagged, below 0 once there are too many flags
+    pub fn remaining(self) -> i32 {
+        self.mines as i32 - self.flags as i32
+    }
+}
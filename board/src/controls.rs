@@ -0,0 +1,162 @@
+use crate::{Buttons, Input, Vec2};
+
+// const expressions
+const CURSOR_MOVE_FRAME_TIMEOUT: u32 = 10;
+
+/// Buttons assigned to each of the player's actions
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Bindings {
+    pub reveal: Buttons,
+    pub cycle: Buttons, // cycles Block -> Flag -> Question -> Block
+    pub flag: Buttons,  // toggles a flag without cycling through the question mark
+    pub chord: Buttons, // reveals the blocks around a number with enough flags next to it
+    pub jump: Buttons,  // held with a direction to jump the cursor
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            reveal: Buttons::A,
+            cycle: Buttons::B,
+            flag: Buttons::R,
+            chord: Buttons::L,
+            jump: Buttons::SELECT,
+        }
+    }
+}
+
+/// Something the player wants to do to the minefield this frame
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Action {
+    Reveal,
+    Cycle,
+    Flag,
+    Chord,
+    Jump(Vec2), // direction to jump in, in blocks
+    Move(Vec2), // amount to move by, in blocks
+    Floor(i32), // floors to go down by, negative to go up
+}
+
+/// A button which is being held down, and acts once it is released
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Hold {
+    Reveal,
+    Chord,
+}
+
+/// Translates button presses into minefield actions
+pub struct Controls {
+    bindings: Bindings,
+    reveal_on_press: bool, // reveal and chord act as soon as they're pressed instead of released
+    floor_buttons: bool,   // L and R go up and down floors while the jump button is held
+    held: Option<Hold>,
+    frames_since_last_move: u32,
+}
+
+impl Controls {
+    pub fn new(bindings: Bindings) -> Self {
+        Self {
+            bindings,
+            reveal_on_press: false,
+            floor_buttons: false,
+            held: None,
+            frames_since_last_move: 0,
+        }
+    }
+
+    pub fn set_bindings(&mut self, bindings: Bindings) -> &mut Self {
+        self.bindings = bindings;
+        self
+    }
+
+    pub fn set_reveal_on_press(&mut self, reveal_on_press: bool) -> &mut Self {
+        self.reveal_on_press = reveal_on_press;
+        self
+    }
+
+    /// Use L and R with the jump button held to go up and down the floors of a board with more
+    /// than one, leaving them to whatever they're bound to on their own
+    pub fn set_floor_buttons(&mut self, floor_buttons: bool) -> &mut Self {
+        self.floor_buttons = floor_buttons;
+        self
+    }
+
+    pub fn held(&self) -> Option<Hold> {
+        self.held
+    }
+
+    pub fn action(&mut self, input: &Input) -> Option<Action> {
+        // Release a held button, acting on it if it was released this frame
+        if let Some(hold) = self.held {
+            let (button, action) = match hold {
+                Hold::Reveal => (self.bindings.reveal, Action::Reveal),
+                Hold::Chord => (self.bindings.chord, Action::Chord),
+            };
+            if !input.is_pressed(button) {
+                self.held = None;
+                if input.is_just_released(button) {
+                    return Some(action);
+                }
+            }
+        }
+
+        if self.floor_buttons && input.is_pressed(self.bindings.jump) {
+            for (button, step) in [(Buttons::L, -1), (Buttons::R, 1)] {
+                if button != self.bindings.jump && input.is_just_pressed(button) {
+                    return Some(Action::Floor(step));
+                }
+            }
+        }
+
+        if input.is_just_pressed(self.bindings.reveal) {
+            if self.reveal_on_press {
+                return Some(Action::Reveal);
+            }
+            self.held = Some(Hold::Reveal);
+            return None;
+        }
+
+        if input.is_just_pressed(self.bindings.cycle) {
+            return Some(Action::Cycle);
+        }
+
+        if input.is_just_pressed(self.bindings.flag) {
+            return Some(Action::Flag);
+        }
+
+        if input.is_just_pressed(self.bindings.chord) {
+            if self.reveal_on_press {
+                return Some(Action::Chord);
+            }
+            self.held = Some(Hold::Chord);
+            return None;
+        }
+
+        let just_pressed_vec = input.just_pressed_vector();
+        let button_vec = input.vector();
+        let zero_vec = Vec2::new(0, 0);
+
+        // Directions pressed while the jump button is held jump rather than move
+        if input.is_pressed(self.bindings.jump) {
+            self.frames_since_last_move = 0;
+            if just_pressed_vec != zero_vec {
+                return Some(Action::Jump(just_pressed_vec));
+            }
+            return None;
+        }
+
+        // Decide whether to move or not while the button is held down
+        if just_pressed_vec != zero_vec {
+            self.frames_since_last_move = 0;
+            return Some(Action::Move(just_pressed_vec));
+        } else if button_vec != zero_vec {
+            if self.frames_since_last_move >= CURSOR_MOVE_FRAME_TIMEOUT {
+                self.frames_since_last_move = 1;
+                return Some(Action::Move(button_vec));
+            }
+            self.frames_since_last_move += 1;
+        }
+
+        None
+    }
+}
@@ -0,0 +1,421 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    Board, Input, MinefieldBlock, MinefieldItem, MinefieldState, Puzzle, Shape, Topology, Vec2,
+    board::ENDLESS_START,
+    controls::{Action, Controls, Hold},
+};
+
+/// How quickly a cascade clears the blocks around a blank block
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum CascadeSpeed {
+    #[default]
+    Classic, // one layer of blocks every few frames
+    Fast,    // one layer of blocks every frame
+    Instant, // the whole cascade in a single frame
+}
+
+impl CascadeSpeed {
+    /// How many frames each layer of the cascade takes, 0 for all of them at once
+    pub fn frames_per_layer(self) -> u32 {
+        match self {
+            CascadeSpeed::Classic => 3,
+            CascadeSpeed::Fast => 1,
+            CascadeSpeed::Instant => 0,
+        }
+    }
+}
+
+/// Something which shows the game to the player
+///
+/// The game calls these as blocks change, so a renderer only ever needs to redraw what it's told
+/// to. `()` renders nothing, for running the game without a display.
+pub trait Renderer {
+    /// Draw a covered block
+    fn draw_block(&mut self, block_pos: Vec2, block: MinefieldBlock);
+
+    /// Draw a cleared block, showing what was underneath it
+    fn draw_cleared_block(&mut self, block_pos: Vec2, item: MinefieldItem);
+
+    /// Draw a covered block which the player is holding down
+    fn draw_pressed_block(&mut self, block_pos: Vec2);
+
+    /// Move the cursor to `block_pos`, which `wrapped` around an edge of the board to get there
+    fn move_cursor(&mut self, block_pos: Vec2, wrapped: bool);
+
+    /// The blocks which can be seen, as the first block and how many across and down, or `None`
+    /// if the whole board can
+    ///
+    /// An endless board only ever draws what can be seen, and a board with several floors may only
+    /// show one of them at a time.
+    fn view(&self) -> Option<(Vec2, Vec2)> {
+        None
+    }
+}
+
+impl Renderer for () {
+    fn draw_block(&mut self, _block_pos: Vec2, _block: MinefieldBlock) {}
+    fn draw_cleared_block(&mut self, _block_pos: Vec2, _item: MinefieldItem) {}
+    fn draw_pressed_block(&mut self, _block_pos: Vec2) {}
+    fn move_cursor(&mut self, _block_pos: Vec2, _wrapped: bool) {}
+}
+
+/// A game of minesweeper being played one frame at a time
+pub struct Game {
+    board: Board,
+    cursor: Vec2,
+    controls: Controls,
+    cursor_wrap: bool, // moving past an edge of the board wraps to the opposite edge
+    pressed_blocks: Vec<Vec2>,
+    cascade_speed: CascadeSpeed,
+    frames_until_block_clear: u32,
+    buffered_action: Option<Action>, // pressed during a cascade, acted on once it's done
+}
+
+impl Game {
+    /// Create a game on a board with block `size` (w x h), with the cursor in the top left
+    pub fn new(size: Vec2) -> Self {
+        Self {
+            board: Board::new(size),
+            cursor: Vec2::new(0, 0),
+            controls: Controls::new(Default::default()),
+            cursor_wrap: false,
+            pressed_blocks: vec![],
+            cascade_speed: CascadeSpeed::default(),
+            frames_until_block_clear: 0,
+            buffered_action: None,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn board_mut(&mut self) -> &mut Board {
+        &mut self.board
+    }
+
+    pub fn controls_mut(&mut self) -> &mut Controls {
+        &mut self.controls
+    }
+
+    pub fn set_cursor_wrap(&mut self, cursor_wrap: bool) -> &mut Self {
+        self.cursor_wrap = cursor_wrap;
+        self
+    }
+
+    pub fn set_cascade_speed(&mut self, cascade_speed: CascadeSpeed) -> &mut Self {
+        self.cascade_speed = cascade_speed;
+        self
+    }
+
+    /// Resize the board, covering every block and keeping the cursor on the board
+    pub fn set_size(&mut self, size: Vec2) -> &mut Self {
+        self.board.set_size(size);
+        self.board_changed()
+    }
+
+    /// Change the board to `shape`, covering every block and keeping the cursor on the board
+    pub fn set_shape(&mut self, shape: &Shape) -> &mut Self {
+        self.board.set_shape(shape);
+        self.board_changed()
+    }
+
+    /// Start `puzzle` from the beginning, with its mines and open blocks in place of random ones
+    pub fn set_puzzle(&mut self, puzzle: &Puzzle) -> &mut Self {
+        self.board.set_puzzle(puzzle);
+        self.board_changed()
+    }
+
+    /// Join the edges of the board up as in `topology`, keeping the cursor on the board
+    pub fn set_topology(&mut self, topology: Topology) -> &mut Self {
+        self.board.set_topology(topology);
+        self.board_changed()
+    }
+
+    /// Stack the board into `floors` floors, covering every block and keeping the cursor on the
+    /// board
+    pub fn set_floors(&mut self, floors: i32) -> &mut Self {
+        self.board.set_floors(floors);
+        self.board_changed()
+    }
+
+    /// Move the cursor onto the nearest block of a new board, forgetting anything in progress
+    ///
+    /// L and R go between floors with the jump button held whenever the board has more than one.
+    fn board_changed(&mut self) -> &mut Self {
+        let size = self.board.size();
+        if !self.board.contains(self.cursor) {
+            self.cursor = Vec2::new(
+                self.cursor.x.clamp(0, size.x - 1),
+                self.cursor.y.clamp(0, size.y * self.board.floors() - 1),
+            );
+        }
+        self.cursor = self.board.nearest_playable(self.cursor);
+        self.controls.set_floor_buttons(self.board.floors() > 1);
+        self.pressed_blocks.clear();
+        self.frames_until_block_clear = 0;
+        self.buffered_action = None;
+        self
+    }
+
+    /// The block the cursor is over
+    pub fn cursor(&self) -> Vec2 {
+        self.cursor
+    }
+
+    /// Cover every block again and place new mines using `next_random`
+    ///
+    /// The cursor goes back to the start of an endless board, which is the only part of it cleared.
+    pub fn reset(&mut self, next_random: impl FnMut() -> i32) {
+        if self.board.topology() == Topology::Endless {
+            self.cursor = ENDLESS_START;
+        }
+        self.board.reset();
+        self.board.gen_mines(next_random);
+        self.pressed_blocks.clear();
+        self.frames_until_block_clear = 0;
+        self.buffered_action = None;
+    }
+
+    fn draw_block(&self, renderer: &mut impl Renderer, block_pos: Vec2) {
+        match self.board.block(block_pos) {
+            MinefieldBlock::Clear => renderer
+                .draw_cleared_block(block_pos, self.board.determine_minefield_item(block_pos)),
+            block => renderer.draw_block(block_pos, block),
+        }
+    }
+
+    /// Run a single frame of the game, with `input` being the buttons held this frame and last
+    pub fn update(&mut self, input: &Input, renderer: &mut impl Renderer) -> MinefieldState {
+        let mut action = self.controls.action(input);
+
+        // Handle clearing blocks on the field if a blank tile was revealed
+        // We return before acting on player input since we don't want the player to be able to
+        // change the board at this point, but hold on to the first thing they pressed
+        if self.board.is_cascading() {
+            if self.buffered_action.is_none() {
+                self.buffered_action = action;
+            }
+            return self.cascade(renderer);
+        }
+
+        // Act on anything pressed during the cascade first, keeping this frame's for the next
+        if let Some(buffered_action) = self.buffered_action.take() {
+            self.buffered_action = action;
+            action = Some(buffered_action);
+        }
+
+        // Handle player input
+        let mut state = match action {
+            Some(Action::Reveal) => self.reveal_block(renderer, self.cursor),
+            Some(Action::Cycle) => {
+                if self.board.cycle_block_state(self.cursor).is_some() {
+                    self.draw_block(renderer, self.cursor);
+                }
+                MinefieldState::Play
+            }
+            Some(Action::Flag) => {
+                if self.board.toggle_flag(self.cursor).is_some() {
+                    self.draw_block(renderer, self.cursor);
+                }
+                MinefieldState::Play
+            }
+            Some(Action::Chord) => self.chord(renderer, self.cursor),
+            Some(Action::Jump(direction)) => {
+                let target = self.board.jump_target(self.cursor, direction);
+                if target != self.cursor {
+                    self.cursor = target;
+                    renderer.move_cursor(target, false);
+                }
+                MinefieldState::Play
+            }
+            Some(Action::Move(move_by)) => {
+                self.move_cursor(renderer, move_by);
+                MinefieldState::Play
+            }
+            Some(Action::Floor(step)) => {
+                if let Some(target) = self.board.floor_step(self.cursor, step) {
+                    self.cursor = target;
+                    renderer.move_cursor(target, false);
+                }
+                MinefieldState::Play
+            }
+            None => MinefieldState::Play,
+        };
+
+        // Make more of an endless board as the cursor gets near the unexplored parts of it
+        self.board.explore(self.cursor);
+
+        // Without an animation, a cascade finishes in the same frame as the reveal which started it
+        if state == MinefieldState::Play
+            && self.board.is_cascading()
+            && self.cascade_speed == CascadeSpeed::Instant
+        {
+            state = self.cascade(renderer);
+        }
+
+        // Show which blocks the player is holding down
+        self.update_pressed_blocks(renderer);
+
+        state
+    }
+
+    fn cascade(&mut self, renderer: &mut impl Renderer) -> MinefieldState {
+        let mut draw_cleared_block = |block_pos, item| {
+            renderer.draw_cleared_block(block_pos, item);
+        };
+
+        // Clear the whole cascade at once if there's no animation
+        let frames_per_layer = self.cascade_speed.frames_per_layer();
+        if frames_per_layer == 0 {
+            let mut state = MinefieldState::Play;
+            while self.board.is_cascading() {
+                state = self.board.cascade_step(&mut draw_cleared_block);
+            }
+            return state;
+        }
+
+        if self.frames_until_block_clear > 0 {
+            self.frames_until_block_clear -= 1;
+            return MinefieldState::Play;
+        }
+
+        self.frames_until_block_clear = frames_per_layer - 1;
+        self.board.cascade_step(draw_cleared_block)
+    }
+
+    fn update_pressed_blocks(&mut self, renderer: &mut impl Renderer) {
+        let mut pressed_blocks = match self.controls.held() {
+            None => vec![],
+            Some(Hold::Reveal) => vec![self.cursor],
+            Some(Hold::Chord) => {
+                let mut surrounding_blocks =
+                    self.board.get_surrounding_uncleared_blocks(self.cursor);
+                surrounding_blocks.push(self.cursor);
+                surrounding_blocks
+            }
+        };
+
+        // Only covered blocks without a flag can be pressed
+        pressed_blocks.retain(|&block| {
+            matches!(
+                self.board.block(block),
+                MinefieldBlock::Block | MinefieldBlock::Question
+            )
+        });
+
+        // Raise blocks which are no longer pressed, unless they've been cleared in the meantime
+        for &block in &self.pressed_blocks {
+            if self.board.block(block) != MinefieldBlock::Clear && !pressed_blocks.contains(&block)
+            {
+                self.draw_block(renderer, block);
+            }
+        }
+
+        for &block in &pressed_blocks {
+            if !self.pressed_blocks.contains(&block) {
+                renderer.draw_pressed_block(block);
+            }
+        }
+        self.pressed_blocks = pressed_blocks;
+    }
+
+    fn reveal_block(&mut self, renderer: &mut impl Renderer, block_pos: Vec2) -> MinefieldState {
+        let state = self.board.reveal_block(block_pos);
+        self.draw_block(renderer, block_pos);
+        state
+    }
+
+    fn chord(&mut self, renderer: &mut impl Renderer, block_pos: Vec2) -> MinefieldState {
+        for block in self.board.chord_blocks(block_pos) {
+            let state = self.reveal_block(renderer, block);
+            if state != MinefieldState::Play {
+                return state;
+            }
+        }
+
+        MinefieldState::Play
+    }
+
+    fn move_cursor(&mut self, renderer: &mut impl Renderer, move_by: Vec2) {
+        let size = self.board.size();
+        let floor_origin = self.board.floor_origin(self.board.floor_of(self.cursor));
+        let mut floor_pos = self.cursor - floor_origin;
+        let mut wrapped = false;
+
+        // Step over any holes in the board, giving up once every block in the way has been tried,
+        // without leaving the cursor's floor
+        for _ in 0..size.x * size.y {
+            floor_pos = self.board.grid().step(floor_pos, move_by);
+            if !self.board.floor_contains(floor_pos) {
+                // Early return if the cursor isn't allowed to wrap around, which it always is when
+                // the edges of the board join up
+                if !self.cursor_wrap && self.board.topology() != Topology::Toroidal {
+                    return;
+                }
+
+                // Nor can it go off the top or bottom of a hex grid whose rows wouldn't line up
+                if !self.board.wraps_vertically() && !(0..size.y).contains(&floor_pos.y) {
+                    return;
+                }
+
+                // Wrap the cursor around to the opposite edge of the board
+                floor_pos = Vec2::new(
+                    floor_pos.x.rem_euclid(size.x),
+                    floor_pos.y.rem_euclid(size.y),
+                );
+                wrapped = true;
+            }
+
+            let target = floor_pos + floor_origin;
+            if target == self.cursor {
+                return;
+            }
+            if self.board.is_playable(target) {
+                self.cursor = target;
+                renderer.move_cursor(target, wrapped);
+                return;
+            }
+        }
+    }
+
+    /// Clear every block on the board, drawing it all again
+    pub fn reveal(&mut self, renderer: &mut impl Renderer) {
+        self.board.reveal();
+        self.draw(renderer);
+    }
+
+    /// Flag every mine which is still covered, drawing the board again
+    pub fn flag_remaining_mines(&mut self, renderer: &mut impl Renderer) {
+        self.board.flag_remaining_mines();
+        self.draw(renderer);
+    }
+
+    /// Draw every block the renderer can see, leaving the holes in the board's shape alone
+    pub fn draw(&self, renderer: &mut impl Renderer) {
+        let board_size = self.board.size();
+        let (first, size) = renderer.view().unwrap_or((
+            Vec2::new(0, 0),
+            Vec2::new(board_size.x, board_size.y * self.board.floors()),
+        ));
+        self.draw_blocks(
+            renderer,
+            (first.y..first.y + size.y)
+                .flat_map(|y| (first.x..first.x + size.x).map(move |x| Vec2::new(x, y))),
+        );
+    }
+
+    /// Draw each of `blocks`, such as those which have just come into view
+    pub fn draw_blocks(
+        &self,
+        renderer: &mut impl Renderer,
+        blocks: impl IntoIterator<Item = Vec2>,
+    ) {
+        for block_pos in blocks {
+            if self.board.is_playable(block_pos) {
+                self.draw_block(renderer, block_pos);
+            }
+        }
+    }
+}
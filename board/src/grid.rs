@@ -0,0 +1,97 @@
+use crate::Vec2;
+
+// Offsets to the blocks around another on a square grid
+const SQUARE_NEIGHBOURS: [Vec2; 8] = [
+    Vec2::new(-1, -1),
+    Vec2::new(0, -1),
+    Vec2::new(1, -1),
+    Vec2::new(-1, 0),
+    Vec2::new(1, 0),
+    Vec2::new(-1, 1),
+    Vec2::new(0, 1),
+    Vec2::new(1, 1),
+];
+
+// On a hex grid odd rows sit half a block to the right of even rows, so the blocks above and
+// below are to the left on even rows and to the right on odd rows
+const HEX_EVEN_ROW_NEIGHBOURS: [Vec2; 6] = [
+    Vec2::new(-1, -1),
+    Vec2::new(0, -1),
+    Vec2::new(-1, 0),
+    Vec2::new(1, 0),
+    Vec2::new(-1, 1),
+    Vec2::new(0, 1),
+];
+const HEX_ODD_ROW_NEIGHBOURS: [Vec2; 6] = [
+    Vec2::new(0, -1),
+    Vec2::new(1, -1),
+    Vec2::new(-1, 0),
+    Vec2::new(1, 0),
+    Vec2::new(0, 1),
+    Vec2::new(1, 1),
+];
+
+/// How the blocks of a board are arranged, which decides the blocks around each one
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Grid {
+    #[default]
+    Square, // 8 blocks around each block
+    Hex, // 6 blocks around each block, with odd rows half a block to the right
+}
+
+impl Grid {
+    /// Offsets to the blocks around a block in `row`
+    pub fn neighbour_offsets(self, row: i32) -> &'static [Vec2] {
+        match self {
+            Grid::Square => &SQUARE_NEIGHBOURS,
+            Grid::Hex if row % 2 == 0 => &HEX_EVEN_ROW_NEIGHBOURS,
+            Grid::Hex => &HEX_ODD_ROW_NEIGHBOURS,
+        }
+    }
+
+    /// The block reached by moving one step in `direction` from `block_pos`
+    ///
+    /// Straight up and down keep to the same column, which zigzags on a hex grid. Diagonals move
+    /// to the block above or below on that side.
+    pub fn step(self, block_pos: Vec2, direction: Vec2) -> Vec2 {
+        let mut step = direction;
+        if self == Grid::Hex && direction.x != 0 && direction.y != 0 {
+            let odd_row = block_pos.y % 2 != 0;
+            if (direction.x > 0) != odd_row {
+                step.x = 0;
+            }
+        }
+        block_pos + step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_steps_reach_neighbours() {
+        for block_pos in [Vec2::new(3, 2), Vec2::new(3, 3)] {
+            let neighbours = Grid::Hex.neighbour_offsets(block_pos.y);
+            for direction in [
+                Vec2::new(-1, -1),
+                Vec2::new(1, -1),
+                Vec2::new(-1, 0),
+                Vec2::new(1, 0),
+                Vec2::new(-1, 1),
+                Vec2::new(1, 1),
+            ] {
+                let offset = Grid::Hex.step(block_pos, direction) - block_pos;
+                assert!(
+                    neighbours.contains(&offset),
+                    "{direction:?} from {block_pos:?}"
+                );
+            }
+        }
+
+        // Up and down zigzag between the two blocks above or below
+        let up = Grid::Hex.step(Vec2::new(3, 3), Vec2::new(0, -1));
+        assert_eq!(up, Vec2::new(3, 2));
+        assert_eq!(Grid::Hex.step(up, Vec2::new(0, -1)), Vec2::new(3, 1));
+    }
+}
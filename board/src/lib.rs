@@ -0,0 +1,40 @@
+//! Minesweeper rules with no knowledge of how the game is drawn or played.
+//!
+//! Everything in here is `no_std` and free of agb, so that it can run on the GBA as well as in
+//! host tests.
+#![no_std]
+
+extern crate alloc;
+
+mod block;
+mod board;
+mod buttons;
+mod cell;
+mod chunks;
+mod clue;
+pub mod controls;
+mod game;
+mod grid;
+mod neighbourhood;
+mod puzzle;
+mod rng;
+mod shape;
+mod simulation;
+mod solver;
+mod topology;
+mod vec2;
+
+pub use block::{MinefieldBlock, MinefieldItem, MinefieldState};
+pub use board::{Board, MAX_FLOORS, MAX_MINES_PER_BLOCK};
+pub use buttons::{Buttons, Input};
+pub use clue::LineClue;
+pub use game::{CascadeSpeed, Game, Renderer};
+pub use grid::Grid;
+pub use neighbourhood::Neighbourhood;
+pub use puzzle::Puzzle;
+pub use rng::Rng;
+pub use shape::Shape;
+pub use simulation::Simulation;
+pub use solver::is_solvable_by_logic;
+pub use topology::Topology;
+pub use vec2::Vec2;
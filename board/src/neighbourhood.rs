@@ -0,0 +1,101 @@
+use crate::{Grid, Vec2};
+
+// The most offsets any neighbourhood has on a single floor
+const MAX_OFFSETS: usize = 24;
+
+// The most blocks any neighbourhood counts around a block, with a floor above and below it
+pub(crate) const MAX_NEIGHBOURS: usize = floored_count(MAX_OFFSETS, 3);
+
+// Offsets to the blocks directly above, below and beside another
+const CROSS: [Vec2; 4] = [
+    Vec2::new(0, -1),
+    Vec2::new(-1, 0),
+    Vec2::new(1, 0),
+    Vec2::new(0, 1),
+];
+
+// Offsets a chess knight could move by
+const KNIGHT: [Vec2; 8] = [
+    Vec2::new(-1, -2),
+    Vec2::new(1, -2),
+    Vec2::new(-2, -1),
+    Vec2::new(2, -1),
+    Vec2::new(-2, 1),
+    Vec2::new(2, 1),
+    Vec2::new(-1, 2),
+    Vec2::new(1, 2),
+];
+
+// Offsets to every other block in the 5x5 square around a block
+const RADIUS_2: [Vec2; MAX_OFFSETS] = radius_2_offsets();
+
+const fn radius_2_offsets() -> [Vec2; MAX_OFFSETS] {
+    let mut offsets = [Vec2::new(0, 0); MAX_OFFSETS];
+    let mut i = 0;
+    let mut y = -2;
+    while y <= 2 {
+        let mut x = -2;
+        while x <= 2 {
+            if x != 0 || y != 0 {
+                offsets[i] = Vec2::new(x, y);
+                i += 1;
+            }
+            x += 1;
+        }
+        y += 1;
+    }
+    offsets
+}
+
+/// Which blocks around a block have their mines counted, and are cleared by a cascade
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Neighbourhood {
+    #[default]
+    Standard, // the blocks touching it on the grid
+    Cross,   // only the blocks above, below and beside it
+    Knight,  // the blocks a knight's move away
+    Radius2, // every block within 2 blocks of it
+}
+
+impl Neighbourhood {
+    pub const ALL: [Neighbourhood; 4] = [
+        Neighbourhood::Standard,
+        Neighbourhood::Cross,
+        Neighbourhood::Knight,
+        Neighbourhood::Radius2,
+    ];
+
+    /// Offsets to the neighbours of a block in `row` of `grid`
+    ///
+    /// The other neighbourhoods are laid out on a square grid, so a hex grid always uses its own
+    /// six neighbours.
+    pub fn offsets(self, grid: Grid, row: i32) -> &'static [Vec2] {
+        match (self, grid) {
+            (Neighbourhood::Standard, _) | (_, Grid::Hex) => grid.neighbour_offsets(row),
+            (Neighbourhood::Cross, Grid::Square) => &CROSS,
+            (Neighbourhood::Knight, Grid::Square) => &KNIGHT,
+            (Neighbourhood::Radius2, Grid::Square) => &RADIUS_2,
+        }
+    }
+
+    /// How many blocks away from a block of `grid` its furthest neighbours are, across or down
+    pub fn reach(self, grid: Grid) -> i32 {
+        match (self, grid) {
+            (Neighbourhood::Knight | Neighbourhood::Radius2, Grid::Square) => 2,
+            _ => 1,
+        }
+    }
+
+    /// The most blocks counted around a block of `grid` on a board with `floors` floors
+    pub fn max_neighbours(self, grid: Grid, floors: i32) -> usize {
+        floored_count(self.offsets(grid, 0).len(), floors)
+    }
+}
+
+/// Blocks counted around one with `offset_count` offsets on its own floor, out of `floors` floors
+///
+/// Each floor next to it adds the block in line with it and the same offsets again.
+const fn floored_count(offset_count: usize, floors: i32) -> usize {
+    let next_floors = if floors < 3 { floors - 1 } else { 2 };
+    offset_count + next_floors as usize * (offset_count + 1)
+}
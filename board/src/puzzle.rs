@@ -0,0 +1,34 @@
+use crate::{Shape, Vec2};
+
+/// A designed board, with its mines and the blocks which start cleared laid out in advance
+///
+/// Rows are written as for a [`Shape`], with `*` for a block with a mine and `o` for a block which
+/// is revealed at the start, cascading as usual.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Puzzle {
+    shape: Shape,
+}
+
+impl Puzzle {
+    pub const fn new(name: &'static str, rows: &'static [&'static str]) -> Self {
+        Self {
+            shape: Shape::new(name, rows),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.shape.name()
+    }
+
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    pub fn has_mine(&self, block_pos: Vec2) -> bool {
+        self.shape.block(block_pos) == Some(b'*')
+    }
+
+    pub fn starts_cleared(&self, block_pos: Vec2) -> bool {
+        self.shape.block(block_pos) == Some(b'o')
+    }
+}
@@ -0,0 +1,24 @@
+/// A small seeded random number generator (xorshift32), so that a game can be replayed exactly
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    pub fn new(seed: u32) -> Self {
+        // xorshift gets stuck on 0, so swap it for an arbitrary non-zero seed
+        let state = if seed == 0 { 0x9e37_79b9 } else { seed };
+        Self { state }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state
+    }
+
+    pub fn next_i32(&mut self) -> i32 {
+        self.next_u32() as i32
+    }
+}
@@ -0,0 +1,60 @@
+use crate::Vec2;
+
+/// The outline of a board, marking which blocks of the rectangle around it are played on
+///
+/// Each row is a string with `.` or a space for a hole and anything else for a block, so that
+/// shapes can be written out by hand as well as generated from level data. Rows shorter than the
+/// longest one are padded with holes.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Shape {
+    name: &'static str,
+    rows: &'static [&'static str],
+}
+
+impl Shape {
+    pub const fn new(name: &'static str, rows: &'static [&'static str]) -> Self {
+        Self { name, rows }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Size of the rectangle around the shape in blocks (w x h)
+    pub fn size(&self) -> Vec2 {
+        let width = self.rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        Vec2::new(width as i32, self.rows.len() as i32)
+    }
+
+    pub fn is_playable(&self, block_pos: Vec2) -> bool {
+        self.block(block_pos)
+            .is_some_and(|block| block != b'.' && block != b' ')
+    }
+
+    /// The character for `block_pos` in the rows, if there is one
+    pub(crate) fn block(&self, block_pos: Vec2) -> Option<u8> {
+        let (Ok(x), Ok(y)) = (usize::try_from(block_pos.x), usize::try_from(block_pos.y)) else {
+            return None;
+        };
+        self.rows
+            .get(y)
+            .and_then(|row| row.as_bytes().get(x))
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_rows_are_padded_with_holes() {
+        let shape = Shape::new("STEPS", &["#", "##", "# #"]);
+        assert_eq!(shape.size(), Vec2::new(3, 3));
+        assert!(shape.is_playable(Vec2::new(1, 1)));
+        assert!(!shape.is_playable(Vec2::new(1, 2)));
+        assert!(!shape.is_playable(Vec2::new(2, 0)));
+        assert!(!shape.is_playable(Vec2::new(-1, 0)));
+        assert!(!shape.is_playable(Vec2::new(0, 3)));
+    }
+}
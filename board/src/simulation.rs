@@ -0,0 +1,102 @@
+use crate::{Buttons, Game, Input, MinefieldState, Rng, Vec2};
+
+/// Runs a game without a display, feeding it scripted button presses one frame at a time
+///
+/// Each frame goes through [`Game::update`], the same as on the GBA, with a renderer which draws
+/// nothing. Mines are placed from a fixed seed so that a run can be reproduced exactly.
+pub struct Simulation {
+    game: Game,
+    input: Input,
+    state: MinefieldState,
+    frame_count: u32,
+}
+
+impl Simulation {
+    /// Start a game with block `size` (w x h) and mines placed randomly from `seed`
+    pub fn new(size: Vec2, seed: u32) -> Self {
+        let mut game = Game::new(size);
+        let mut rng = Rng::new(seed);
+        game.reset(|| rng.next_i32());
+        Self::with_game(game)
+    }
+
+    /// Start a game with block `size` (w x h) and mines at exactly the positions in `mines`
+    pub fn with_mines(size: Vec2, mines: &[Vec2]) -> Self {
+        let mut game = Game::new(size);
+        for &mine in mines {
+            game.board_mut().set_mine(mine, true);
+        }
+        Self::with_game(game)
+    }
+
+    /// Run an already set up game
+    pub fn with_game(game: Game) -> Self {
+        Self {
+            game,
+            input: Input::default(),
+            state: MinefieldState::Play,
+            frame_count: 0,
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn game_mut(&mut self) -> &mut Game {
+        &mut self.game
+    }
+
+    pub fn state(&self) -> MinefieldState {
+        self.state
+    }
+
+    /// How many frames have been run so far
+    pub fn frame_count(&self) -> u32 {
+        self.frame_count
+    }
+
+    /// Run one frame with `buttons` held, unless the game is already over
+    pub fn frame(&mut self, buttons: Buttons) -> MinefieldState {
+        if self.state != MinefieldState::Play {
+            return self.state;
+        }
+
+        self.input.update(buttons);
+        self.state = self.game.update(&self.input, &mut ());
+        self.frame_count += 1;
+        self.state
+    }
+
+    /// Hold `buttons` for `frames` frames
+    pub fn hold(&mut self, buttons: Buttons, frames: u32) -> MinefieldState {
+        for _ in 0..frames {
+            self.frame(buttons);
+        }
+        self.state
+    }
+
+    /// Press and release `buttons`, taking two frames
+    pub fn tap(&mut self, buttons: Buttons) -> MinefieldState {
+        self.frame(buttons);
+        self.frame(Buttons::NONE)
+    }
+
+    /// Run a script of buttons to hold and how many frames to hold them for
+    pub fn run(&mut self, script: &[(Buttons, u32)]) -> MinefieldState {
+        for &(buttons, frames) in script {
+            self.hold(buttons, frames);
+        }
+        self.state
+    }
+
+    /// Run frames with nothing pressed until any cascade has finished, returning how many frames
+    /// it took
+    pub fn settle(&mut self) -> u32 {
+        let start = self.frame_count;
+        while self.state == MinefieldState::Play && self.game.board().is_cascading() {
+            self.frame(Buttons::NONE);
+        }
+        self.frame_count - start
+    }
+}
@@ -0,0 +1,138 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{Board, MinefieldBlock, MinefieldItem, MinefieldState, Vec2};
+
+/// What a cleared block's number says about the covered blocks around it
+struct Constraint {
+    blocks: Vec<Vec2>, // covered blocks which aren't known to be mines yet
+    mines: u32,        // how many of them are mines
+}
+
+/// Whether the player could win `board` from where it is without ever having to guess
+///
+/// Blocks are only cleared once the numbers prove them safe, going by one number at a time or by
+/// comparing two numbers whose covered blocks overlap. Only boards with one mine per block are
+/// supported.
+pub fn is_solvable_by_logic(board: &Board) -> bool {
+    let mut board = board.clone();
+    let size = board.size();
+    let mut known_mines = vec![false; (size.x * size.y) as usize];
+    loop {
+        if board.is_win_condition() {
+            return true;
+        }
+
+        let constraints = constraints(&board, &known_mines);
+        let mut safe_blocks = vec![];
+        let mut mines = vec![];
+        for constraint in &constraints {
+            deduce(
+                &constraint.blocks,
+                constraint.mines,
+                &mut safe_blocks,
+                &mut mines,
+            );
+        }
+
+        // When all of one number's blocks are around another, the rest of the other's blocks hold
+        // the difference
+        for inner in &constraints {
+            for outer in &constraints {
+                if inner.blocks.len() >= outer.blocks.len()
+                    || !inner
+                        .blocks
+                        .iter()
+                        .all(|block| outer.blocks.contains(block))
+                {
+                    continue;
+                }
+                let Some(rest_mines) = outer.mines.checked_sub(inner.mines) else {
+                    continue;
+                };
+                let rest: Vec<Vec2> = outer
+                    .blocks
+                    .iter()
+                    .copied()
+                    .filter(|block| !inner.blocks.contains(block))
+                    .collect();
+                deduce(&rest, rest_mines, &mut safe_blocks, &mut mines);
+            }
+        }
+
+        // Stuck, so the player would have to guess
+        if safe_blocks.is_empty() && mines.is_empty() {
+            return false;
+        }
+
+        for mine in mines {
+            known_mines[(mine.x + mine.y * size.x) as usize] = true;
+        }
+        for block in safe_blocks {
+            if board.reveal_block(block) == MinefieldState::GameOver(false) {
+                return false;
+            }
+            while board.is_cascading() {
+                board.cascade_step(|_, _| ());
+            }
+        }
+    }
+}
+
+/// A constraint for every cleared block next to covered blocks which aren't known to be mines
+fn constraints(board: &Board, known_mines: &[bool]) -> Vec<Constraint> {
+    let size = board.size();
+    let mut constraints = vec![];
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let block_pos = Vec2::new(x, y);
+            if !board.is_playable(block_pos) || board.block(block_pos) != MinefieldBlock::Clear {
+                continue;
+            }
+            let mines = match board.determine_minefield_item(block_pos) {
+                MinefieldItem::Number(mines) => mines,
+                MinefieldItem::Blank => 0,
+                MinefieldItem::Mine(_) => continue,
+            };
+
+            let (known, blocks): (Vec<Vec2>, Vec<Vec2>) = board
+                .get_surrounding_uncleared_blocks(block_pos)
+                .into_iter()
+                .partition(|block| known_mines[(block.x + block.y * size.x) as usize]);
+            if let (false, Some(mines)) = (blocks.is_empty(), mines.checked_sub(known.len() as u32))
+            {
+                constraints.push(Constraint { blocks, mines });
+            }
+        }
+    }
+    constraints
+}
+
+/// Add `blocks` to `safe_blocks` if none of them are mines, or to `found_mines` if they all are
+fn deduce(blocks: &[Vec2], mines: u32, safe_blocks: &mut Vec<Vec2>, found_mines: &mut Vec<Vec2>) {
+    if blocks.is_empty() {
+        return;
+    }
+    if mines == 0 {
+        safe_blocks.extend_from_slice(blocks);
+    } else if mines as usize == blocks.len() {
+        found_mines.extend_from_slice(blocks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Puzzle;
+
+    #[test]
+    fn solves_only_without_guessing() {
+        // The 1 next to the corner says which of the two blocks beside it is the mine
+        let mut board = Board::new(Vec2::new(1, 1));
+        board.set_puzzle(&Puzzle::new("EASY", &["o##", "o#*", "ooo"]));
+        assert!(is_solvable_by_logic(&board));
+
+        // Either block could be the mine
+        board.set_puzzle(&Puzzle::new("COIN", &["*#", "oo"]));
+        assert!(!is_solvable_by_logic(&board));
+    }
+}
@@ -0,0 +1,31 @@
+use crate::Vec2;
+
+/// What happens at the edges of a board
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Topology {
+    #[default]
+    Bounded, // nothing past the edges
+    Toroidal, // each edge joins onto the opposite one, left to right and top to bottom
+    Endless,  // no edges at all, with more of the board made as it's explored
+}
+
+impl Topology {
+    /// The block on a board with block `size` (w x h) found at `block_pos`, which may be past an
+    /// edge, or `None` if there isn't one
+    ///
+    /// An endless board goes on past `size` in every direction.
+    pub fn block_at(self, size: Vec2, block_pos: Vec2) -> Option<Vec2> {
+        match self {
+            Topology::Bounded => (block_pos.x >= 0
+                && block_pos.y >= 0
+                && block_pos.x < size.x
+                && block_pos.y < size.y)
+                .then_some(block_pos),
+            Topology::Toroidal => Some(Vec2::new(
+                block_pos.x.rem_euclid(size.x),
+                block_pos.y.rem_euclid(size.y),
+            )),
+            Topology::Endless => Some(block_pos),
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//! Invariants of the board rules, checked over many randomly generated boards
+
+use minesweeper_board::{
+    Board, Buttons, Game, Grid, MinefieldBlock, MinefieldItem, MinefieldState, Neighbourhood, Rng,
+    Shape, Simulation, Topology, Vec2,
+};
+
+const SEEDS: u32 = 200;
+
+/// A random board for `seed`, between 1x1 and 30x20 blocks with up to 1, 2 or 3 mines per block
+fn random_board(seed: u32) -> (Board, Rng) {
+    let mut rng = Rng::new(seed);
+    let size = Vec2::new(
+        1 + (rng.next_u32() % 30) as i32,
+        1 + (rng.next_u32() % 20) as i32,
+    );
+    let mut board = Board::new(size);
+    board.set_max_mines_per_block(1 + seed % 3);
+    board.gen_mines(|| rng.next_i32());
+    (board, rng)
+}
+
+fn block_positions(board: &Board) -> impl Iterator<Item = Vec2> + use<> {
+    let size = board.size();
+    (0..size.y).flat_map(move |y| (0..size.x).map(move |x| Vec2::new(x, y)))
+}
+
+fn neighbours(board: &Board, block_pos: Vec2) -> Vec<Vec2> {
+    let mut neighbours = vec![];
+    for y_offset in -1..=1 {
+        for x_offset in -1..=1 {
+            let neighbour = block_pos + Vec2::new(x_offset, y_offset);
+            if neighbour != block_pos && board.contains(neighbour) {
+                neighbours.push(neighbour);
+            }
+        }
+    }
+    neighbours
+}
+
+fn all_safe_blocks_clear(board: &Board) -> bool {
+    block_positions(board).all(|block_pos| {
+        board.is_mine(block_pos) || board.block(block_pos) == MinefieldBlock::Clear
+    })
+}
+
+/// Reveal a random safe block and run the cascade to the end, returning the final state
+fn reveal_random_safe_block(board: &mut Board, rng: &mut Rng) -> Option<MinefieldState> {
+    let safe_blocks: Vec<Vec2> = block_positions(board)
+        .filter(|&block_pos| {
+            !board.is_mine(block_pos) && board.block(block_pos) != MinefieldBlock::Clear
+        })
+        .collect();
+    if safe_blocks.is_empty() {
+        return None;
+    }
+
+    let block_pos = safe_blocks[rng.next_u32() as usize % safe_blocks.len()];
+    let mut state = board.reveal_block(block_pos);
+    while board.is_cascading() {
+        state = board.cascade_step(|cleared, item| {
+            assert!(
+                !matches!(item, MinefieldItem::Mine(_)),
+                "cascade cleared a mine at {cleared:?}"
+            );
+        });
+    }
+    Some(state)
+}
+
+#[test]
+fn cascade_never_reveals_a_mine() {
+    for seed in 0..SEEDS {
+        let (mut board, mut rng) = random_board(seed);
+        while reveal_random_safe_block(&mut board, &mut rng).is_some() {
+            for block_pos in block_positions(&board) {
+                if board.is_mine(block_pos) {
+                    assert_ne!(board.block(block_pos), MinefieldBlock::Clear, "seed {seed}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn revealed_blanks_have_all_neighbours_revealed() {
+    for seed in 0..SEEDS {
+        let (mut board, mut rng) = random_board(seed);
+        while reveal_random_safe_block(&mut board, &mut rng).is_some() {
+            for block_pos in block_positions(&board) {
+                if board.block(block_pos) != MinefieldBlock::Clear
+                    || board.determine_minefield_item(block_pos) != MinefieldItem::Blank
+                {
+                    continue;
+                }
+                for neighbour in neighbours(&board, block_pos) {
+                    assert_eq!(
+                        board.block(neighbour),
+                        MinefieldBlock::Clear,
+                        "seed {seed}: {neighbour:?} next to blank {block_pos:?}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn numbers_match_adjacent_mine_count() {
+    for seed in 0..SEEDS {
+        let (board, _) = random_board(seed);
+        for block_pos in block_positions(&board) {
+            let mine_count = neighbours(&board, block_pos)
+                .into_iter()
+                .map(|neighbour| board.mine_count(neighbour))
+                .sum();
+            let expected = if board.is_mine(block_pos) {
+                MinefieldItem::Mine(board.mine_count(block_pos))
+            } else if mine_count == 0 {
+                MinefieldItem::Blank
+            } else {
+                MinefieldItem::Number(mine_count)
+            };
+            assert_eq!(
+                board.determine_minefield_item(block_pos),
+                expected,
+                "seed {seed}: {block_pos:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn neighbours_are_mutual_and_counted() {
+    for seed in 0..SEEDS {
+        let (mut board, _) = random_board(seed);
+        match seed % 3 {
+            0 => board.set_grid(Grid::Hex),
+            1 => board.set_topology(Topology::Toroidal),
+            _ => board.set_grid(Grid::Hex).set_topology(Topology::Toroidal),
+        };
+        board.set_neighbourhood(Neighbourhood::ALL[(seed / 3) as usize % 4]);
+        for block_pos in block_positions(&board) {
+            let mut mine_count = 0;
+            for neighbour in board.neighbours(block_pos) {
+                assert!(
+                    board.neighbours(neighbour).any(|back| back == block_pos),
+                    "seed {seed}: {neighbour:?} doesn't border {block_pos:?}"
+                );
+                mine_count += board.mine_count(neighbour);
+            }
+            if !board.is_mine(block_pos) && mine_count > 0 {
+                assert_eq!(
+                    board.determine_minefield_item(block_pos),
+                    MinefieldItem::Number(mine_count),
+                    "seed {seed}: {block_pos:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn win_exactly_when_all_safe_blocks_clear() {
+    for seed in 0..SEEDS {
+        let (mut board, mut rng) = random_board(seed);
+        assert_eq!(board.is_win_condition(), all_safe_blocks_clear(&board));
+
+        while let Some(state) = reveal_random_safe_block(&mut board, &mut rng) {
+            let won = all_safe_blocks_clear(&board);
+            assert_eq!(board.is_win_condition(), won, "seed {seed}");
+            assert_eq!(state == MinefieldState::GameOver(true), won, "seed {seed}");
+        }
+    }
+}
+
+#[test]
+fn random_play_keeps_invariants() {
+    let buttons = [
+        Buttons::A,
+        Buttons::B,
+        Buttons::L,
+        Buttons::R,
+        Buttons::UP,
+        Buttons::DOWN,
+        Buttons::LEFT,
+        Buttons::RIGHT,
+        Buttons::SELECT | Buttons::RIGHT,
+        Buttons::SELECT | Buttons::DOWN,
+        Buttons::NONE,
+    ];
+    for seed in 0..SEEDS {
+        let (board, mut rng) = random_board(seed);
+        let mut sim = Simulation::with_mines(
+            board.size(),
+            &block_positions(&board)
+                .filter(|&block_pos| board.is_mine(block_pos))
+                .collect::<Vec<_>>(),
+        );
+
+        // Mash buttons through the same path as the GBA until the game ends
+        for _ in 0..2000 {
+            let pressed = buttons[rng.next_u32() as usize % buttons.len()];
+            let frames = 1 + rng.next_u32() % 4;
+            let state = sim.hold(pressed, frames);
+
+            let board = sim.game().board();
+            let cursor = sim.game().cursor();
+            assert!(board.contains(cursor), "seed {seed}: cursor left the board");
+
+            let mine_cleared = block_positions(board).any(|block_pos| {
+                board.is_mine(block_pos) && board.block(block_pos) == MinefieldBlock::Clear
+            });
+            match state {
+                MinefieldState::Play => {
+                    assert!(!mine_cleared, "seed {seed}");
+                }
+                MinefieldState::GameOver(false) => {
+                    assert!(mine_cleared, "seed {seed}");
+                    break;
+                }
+                MinefieldState::GameOver(true) => {
+                    assert!(all_safe_blocks_clear(board), "seed {seed}");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn holes_are_never_played() {
+    // Shapes with holes in the middle, gaps along the edges and parts only joined diagonally
+    let shapes = [
+        Shape::new("RING", &["#####", "#...#", "#.#.#", "#...#", "#####"]),
+        Shape::new("CROSS", &[".##.", "####", "####", ".##."]),
+        Shape::new("CHECKS", &["#.#.#.", ".#.#.#", "#.#.#."]),
+    ];
+    let buttons = [
+        Buttons::A,
+        Buttons::B,
+        Buttons::UP,
+        Buttons::DOWN,
+        Buttons::LEFT,
+        Buttons::RIGHT,
+        Buttons::SELECT | Buttons::RIGHT,
+        Buttons::SELECT | Buttons::DOWN,
+        Buttons::NONE,
+    ];
+    for seed in 0..SEEDS {
+        let shape = &shapes[seed as usize % shapes.len()];
+        let mut rng = Rng::new(seed);
+        let mut game = Game::new(Vec2::new(1, 1));
+        game.set_shape(shape).set_cursor_wrap(seed % 2 == 0);
+        game.reset(|| rng.next_i32());
+        let mut sim = Simulation::with_game(game);
+
+        for _ in 0..2000 {
+            let pressed = buttons[rng.next_u32() as usize % buttons.len()];
+            let frames = 1 + rng.next_u32() % 4;
+            let state = sim.hold(pressed, frames);
+
+            let board = sim.game().board();
+            let cursor = sim.game().cursor();
+            assert!(shape.is_playable(cursor), "seed {seed}: cursor on a hole");
+            for block_pos in block_positions(board) {
+                if !shape.is_playable(block_pos) {
+                    assert!(!board.is_mine(block_pos), "seed {seed}: mine in a hole");
+                    assert_eq!(board.block(block_pos), MinefieldBlock::Block, "seed {seed}");
+                }
+            }
+            if state != MinefieldState::Play {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn endless_numbers_match_their_mines_wherever_explored() {
+    for seed in 0..SEEDS {
+        let mut rng = Rng::new(seed);
+        let mut board = Board::new(Vec2::new(1, 1));
+        board
+            .set_max_mines_per_block(1 + seed % 3)
+            .set_neighbourhood(Neighbourhood::ALL[(seed / 3) as usize % 4])
+            .set_topology(Topology::Endless);
+        if seed % 2 == 0 {
+            board.set_grid(Grid::Hex);
+        }
+        board.gen_mines(|| rng.next_i32());
+
+        // Somewhere well away from the start, in any direction
+        let far = Vec2::new(
+            (rng.next_u32() % 2000) as i32 - 1000,
+            (rng.next_u32() % 2000) as i32 - 1000,
+        );
+        board.explore(far);
+        for y in -4..=4 {
+            for x in -4..=4 {
+                let block_pos = far + Vec2::new(x, y);
+                let mine_count = board
+                    .neighbours(block_pos)
+                    .map(|neighbour| board.mine_count(neighbour))
+                    .sum();
+                let expected = if board.is_mine(block_pos) {
+                    MinefieldItem::Mine(board.mine_count(block_pos))
+                } else if mine_count == 0 {
+                    MinefieldItem::Blank
+                } else {
+                    MinefieldItem::Number(mine_count)
+                };
+                assert_eq!(
+                    board.determine_minefield_item(block_pos),
+                    expected,
+                    "seed {seed}: {block_pos:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn floored_numbers_count_the_cube_around_them() {
+    for seed in 0..SEEDS {
+        let (mut board, mut rng) = random_board(seed);
+        let floors = 2 + (seed % 2) as i32;
+        board.set_floors(floors);
+        board.gen_mines(|| rng.next_i32());
+
+        let size = board.size();
+        for y in 0..size.y * floors {
+            for x in 0..size.x {
+                let block_pos = Vec2::new(x, y);
+                let floor = board.floor_of(block_pos);
+                let floor_pos = block_pos - board.floor_origin(floor);
+
+                // Every block in the 3x3x3 cube around it which is on the board
+                let mut mine_count = 0;
+                for floor_step in -1..=1 {
+                    for y_offset in -1..=1 {
+                        for x_offset in -1..=1 {
+                            let offset = Vec2::new(x_offset, y_offset);
+                            let neighbour = floor_pos + offset;
+                            let on_board = (0..floors).contains(&(floor + floor_step))
+                                && (0..size.x).contains(&neighbour.x)
+                                && (0..size.y).contains(&neighbour.y);
+                            if on_board && (floor_step, offset) != (0, Vec2::new(0, 0)) {
+                                mine_count += board
+                                    .mine_count(neighbour + board.floor_origin(floor + floor_step));
+                            }
+                        }
+                    }
+                }
+                let expected = if board.is_mine(block_pos) {
+                    MinefieldItem::Mine(board.mine_count(block_pos))
+                } else if mine_count == 0 {
+                    MinefieldItem::Blank
+                } else {
+                    MinefieldItem::Number(mine_count)
+                };
+                assert_eq!(
+                    board.determine_minefield_item(block_pos),
+                    expected,
+                    "seed {seed}: {block_pos:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn crowded_floors_count_every_mine_around_them() {
+    let mut most_counted = 0;
+    for seed in 0..SEEDS {
+        let (mut board, mut rng) = random_board(seed);
+        let floors = 1 + (seed % 3) as i32;
+        let neighbourhood = Neighbourhood::ALL[(seed / 3) as usize % 4];
+        let max_mines = board.max_mines_per_block();
+        board.set_floors(floors).set_neighbourhood(neighbourhood);
+
+        // Fill most blocks, so that the counts get as high as the neighbourhood allows
+        let size = board.size();
+        for y in 0..size.y * floors {
+            for x in 0..size.x {
+                let mines = if rng.next_u32() % 8 == 0 {
+                    0
+                } else {
+                    max_mines
+                };
+                board.set_mines(Vec2::new(x, y), mines);
+            }
+        }
+
+        let max_count = neighbourhood.max_neighbours(board.grid(), floors) as u32 * max_mines;
+        for y in 0..size.y * floors {
+            for x in 0..size.x {
+                let block_pos = Vec2::new(x, y);
+                assert_eq!(board.block(block_pos), MinefieldBlock::Block, "seed {seed}");
+                assert_eq!(board.mine_count(block_pos) > 0, board.is_mine(block_pos));
+                if board.is_mine(block_pos) {
+                    continue;
+                }
+
+                let mine_count = board
+                    .neighbours(block_pos)
+                    .map(|neighbour| board.mine_count(neighbour))
+                    .sum();
+                assert!(mine_count <= max_count, "seed {seed}: {block_pos:?}");
+                if mine_count > 0 {
+                    assert_eq!(
+                        board.determine_minefield_item(block_pos),
+                        MinefieldItem::Number(mine_count),
+                        "seed {seed}: {block_pos:?}"
+                    );
+                }
+                most_counted = most_counted.max(mine_count);
+            }
+        }
+    }
+
+    // Past what 7 bits could hold, so a narrower count would have been caught
+    assert!(most_counted > 127, "counted at most {most_counted}");
+}
@@ -0,0 +1,358 @@
+use minesweeper_board::{
+    Buttons, CascadeSpeed, Game, Grid, MinefieldBlock, MinefieldItem, MinefieldState, Puzzle, Rng,
+    Shape, Simulation, Topology, Vec2,
+};
+
+#[test]
+fn same_seed_places_same_mines() {
+    let size = Vec2::new(13, 8);
+    let first = Simulation::new(size, 1234);
+    let second = Simulation::new(size, 1234);
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let block_pos = Vec2::new(x, y);
+            assert_eq!(
+                first.game().board().is_mine(block_pos),
+                second.game().board().is_mine(block_pos)
+            );
+        }
+    }
+}
+
+#[test]
+fn reveal_happens_on_release() {
+    let mut sim = Simulation::with_mines(Vec2::new(3, 3), &[Vec2::new(2, 2)]);
+    sim.hold(Buttons::RIGHT, 1);
+    sim.hold(Buttons::A, 5);
+    assert_eq!(
+        sim.game().board().block(Vec2::new(1, 0)),
+        MinefieldBlock::Block
+    );
+
+    sim.frame(Buttons::NONE);
+    assert_eq!(
+        sim.game().board().block(Vec2::new(1, 0)),
+        MinefieldBlock::Clear
+    );
+    assert_eq!(sim.state(), MinefieldState::Play);
+}
+
+#[test]
+fn held_direction_repeats_after_timeout() {
+    let mut sim = Simulation::with_mines(Vec2::new(13, 8), &[]);
+
+    // One move on the press, then one every 10 frames after the first 10
+    sim.hold(Buttons::RIGHT, 25);
+    assert_eq!(sim.game().cursor(), Vec2::new(3, 0));
+
+    // The cursor stops at the edge unless wrapping is turned on
+    sim.hold(Buttons::RIGHT, 200);
+    assert_eq!(sim.game().cursor(), Vec2::new(12, 0));
+    sim.game_mut().set_cursor_wrap(true);
+    sim.frame(Buttons::NONE);
+    sim.tap(Buttons::RIGHT);
+    assert_eq!(sim.game().cursor(), Vec2::new(0, 0));
+}
+
+#[test]
+fn resizing_keeps_cursor_on_board() {
+    let mut sim = Simulation::with_mines(Vec2::new(13, 8), &[]);
+    sim.hold(Buttons::RIGHT, 200);
+    sim.hold(Buttons::DOWN, 200);
+    assert_eq!(sim.game().cursor(), Vec2::new(12, 7));
+
+    // Shrinking moves the cursor onto the nearest block which is left
+    sim.game_mut().set_size(Vec2::new(9, 9));
+    assert_eq!(sim.game().cursor(), Vec2::new(8, 7));
+
+    // Growing leaves it where it is
+    sim.game_mut().set_size(Vec2::new(30, 16));
+    assert_eq!(sim.game().cursor(), Vec2::new(8, 7));
+}
+
+#[test]
+fn hex_cursor_only_wraps_vertically_with_even_rows() {
+    let mut sim = Simulation::with_mines(Vec2::new(5, 3), &[]);
+    sim.game_mut().set_topology(Topology::Toroidal);
+    sim.game_mut().board_mut().set_grid(Grid::Hex);
+
+    // The rows wouldn't line up going off the top of an odd number of them, but the sides still join
+    sim.tap(Buttons::UP);
+    assert_eq!(sim.game().cursor(), Vec2::new(0, 0));
+    sim.tap(Buttons::LEFT);
+    assert_eq!(sim.game().cursor(), Vec2::new(4, 0));
+
+    // Cursor wrap on a bounded board keeps to the same rule
+    sim.game_mut()
+        .set_topology(Topology::Bounded)
+        .set_cursor_wrap(true);
+    sim.tap(Buttons::UP);
+    assert_eq!(sim.game().cursor(), Vec2::new(4, 0));
+
+    sim.game_mut().set_size(Vec2::new(5, 4));
+    sim.tap(Buttons::UP);
+    assert_eq!(sim.game().cursor(), Vec2::new(4, 3));
+}
+
+#[test]
+fn cursor_steps_over_holes() {
+    let shape = Shape::new("GAP", &["##.##", "#...#"]);
+    let mut game = Game::new(Vec2::new(1, 1));
+    game.set_shape(&shape);
+    let mut sim = Simulation::with_game(game);
+
+    // Moving right jumps the gap in the top row, then stops at the edge
+    sim.tap(Buttons::RIGHT);
+    sim.tap(Buttons::RIGHT);
+    assert_eq!(sim.game().cursor(), Vec2::new(3, 0));
+    sim.tap(Buttons::RIGHT);
+    sim.tap(Buttons::RIGHT);
+    assert_eq!(sim.game().cursor(), Vec2::new(4, 0));
+
+    // There's nothing below the middle of the top row, so the cursor stays put
+    sim.tap(Buttons::LEFT);
+    sim.tap(Buttons::DOWN);
+    assert_eq!(sim.game().cursor(), Vec2::new(3, 0));
+
+    // A new shape moves the cursor onto its nearest block
+    sim.game_mut()
+        .set_shape(&Shape::new("DOT", &["...", "..#"]));
+    assert_eq!(sim.game().cursor(), Vec2::new(2, 1));
+}
+
+#[test]
+fn shoulder_buttons_with_select_switch_floors() {
+    let mut game = Game::new(Vec2::new(4, 3));
+    game.set_floors(3);
+    let mut sim = Simulation::with_game(game);
+
+    // SELECT+R goes down a floor and SELECT+L back up, keeping to the same place on each floor
+    sim.tap(Buttons::RIGHT);
+    sim.tap(Buttons::SELECT | Buttons::R);
+    assert_eq!(sim.game().cursor(), Vec2::new(1, 3));
+    sim.tap(Buttons::SELECT | Buttons::R);
+    sim.tap(Buttons::SELECT | Buttons::R);
+    assert_eq!(sim.game().cursor(), Vec2::new(1, 6));
+    sim.tap(Buttons::SELECT | Buttons::L);
+    assert_eq!(sim.game().cursor(), Vec2::new(1, 3));
+
+    // Moving off a floor wraps around it or stops, rather than going onto the next floor
+    sim.tap(Buttons::UP);
+    assert_eq!(sim.game().cursor(), Vec2::new(1, 3));
+    sim.game_mut().set_cursor_wrap(true);
+    sim.tap(Buttons::UP);
+    assert_eq!(sim.game().cursor(), Vec2::new(1, 5));
+
+    // Without floors there's nowhere to go, so SELECT+R just flags
+    sim.game_mut().set_floors(1);
+    assert_eq!(sim.game().cursor(), Vec2::new(1, 2));
+    sim.tap(Buttons::SELECT | Buttons::R);
+    assert_eq!(
+        sim.game().board().block(Vec2::new(1, 2)),
+        MinefieldBlock::Flag(1)
+    );
+}
+
+#[test]
+fn flag_and_chord_on_floors() {
+    let mut game = Game::new(Vec2::new(3, 3));
+    game.set_floors(3);
+    game.board_mut().set_mine(Vec2::new(0, 3), true);
+    let mut sim = Simulation::with_game(game);
+
+    // Reveal the 1 in the middle of the middle floor
+    sim.tap(Buttons::SELECT | Buttons::R);
+    sim.tap(Buttons::DOWN);
+    sim.tap(Buttons::RIGHT);
+    assert_eq!(sim.game().cursor(), Vec2::new(1, 4));
+    sim.tap(Buttons::A);
+    assert_eq!(
+        sim.game().board().determine_minefield_item(Vec2::new(1, 4)),
+        MinefieldItem::Number(1)
+    );
+
+    // R still flags and L still chords, reaching the floors above and below
+    sim.tap(Buttons::UP);
+    sim.tap(Buttons::LEFT);
+    sim.tap(Buttons::R);
+    assert_eq!(
+        sim.game().board().block(Vec2::new(0, 3)),
+        MinefieldBlock::Flag(1)
+    );
+    sim.tap(Buttons::DOWN);
+    sim.tap(Buttons::RIGHT);
+    sim.tap(Buttons::L);
+    sim.settle();
+    assert_eq!(sim.state(), MinefieldState::GameOver(true));
+    assert_eq!(
+        sim.game().board().block(Vec2::new(1, 1)),
+        MinefieldBlock::Clear
+    );
+    assert_eq!(
+        sim.game().board().block(Vec2::new(1, 7)),
+        MinefieldBlock::Clear
+    );
+}
+
+#[test]
+fn puzzles_start_with_their_layout() {
+    let mut game = Game::new(Vec2::new(1, 1));
+    game.set_puzzle(&Puzzle::new("TRIO", &["o*#"]));
+    let mut sim = Simulation::with_game(game);
+    let board = sim.game().board();
+    assert_eq!(board.block(Vec2::new(0, 0)), MinefieldBlock::Clear);
+    assert!(board.is_mine(Vec2::new(1, 0)));
+    assert!(!board.is_win_condition());
+
+    // Clearing the only other safe block wins
+    sim.tap(Buttons::RIGHT);
+    sim.tap(Buttons::RIGHT);
+    assert_eq!(sim.tap(Buttons::A), MinefieldState::GameOver(true));
+}
+
+#[test]
+fn endless_board_scores_until_a_mine() {
+    let mut game = Game::new(Vec2::new(13, 8));
+    game.set_topology(Topology::Endless);
+    let mut rng = Rng::new(99);
+    game.reset(|| rng.next_i32());
+    let mut sim = Simulation::with_game(game);
+    assert_eq!(sim.game().cursor(), Vec2::new(0, 0));
+    assert_eq!(sim.game().board().cleared_blocks(), 0);
+
+    // There's no edge to stop at, so the cursor carries on into negative blocks
+    sim.hold(Buttons::LEFT, 400);
+    sim.hold(Buttons::UP, 400);
+    let cursor = sim.game().cursor();
+    assert!(cursor.x < -20 && cursor.y < -20, "{cursor:?}");
+
+    // Each safe block cleared adds to the score
+    let covered = |sim: &Simulation, mine: bool| {
+        (0..)
+            .map(|x| cursor + Vec2::new(x, 0))
+            .find(|&block_pos| {
+                let board = sim.game().board();
+                board.is_mine(block_pos) == mine && board.block(block_pos) == MinefieldBlock::Block
+            })
+            .unwrap()
+    };
+    let safe = covered(&sim, false);
+    while sim.game().cursor() != safe {
+        sim.tap(Buttons::RIGHT);
+    }
+    assert_eq!(sim.tap(Buttons::A), MinefieldState::Play);
+    sim.settle();
+    assert!(sim.game().board().cleared_blocks() > 0);
+
+    // Walking onto a mine always loses, however much has been cleared
+    let mine = covered(&sim, true);
+    while sim.game().cursor() != mine {
+        let step = if sim.game().cursor().x < mine.x {
+            Buttons::RIGHT
+        } else {
+            Buttons::LEFT
+        };
+        sim.tap(step);
+    }
+    assert_eq!(sim.tap(Buttons::A), MinefieldState::GameOver(false));
+}
+
+#[test]
+fn cascade_clears_one_layer_every_three_frames() {
+    let mut sim = Simulation::with_mines(Vec2::new(6, 1), &[Vec2::new(5, 0)]);
+    sim.tap(Buttons::A);
+    assert_eq!(
+        sim.game().board().block(Vec2::new(1, 0)),
+        MinefieldBlock::Block
+    );
+
+    // Blocks 1 to 4 are cleared one at a time, the last one finishing the board
+    assert_eq!(sim.settle(), 10);
+    assert_eq!(sim.state(), MinefieldState::GameOver(true));
+}
+
+#[test]
+fn faster_cascades_take_fewer_frames() {
+    let mines = [Vec2::new(5, 0)];
+    let mut sim = Simulation::with_mines(Vec2::new(6, 1), &mines);
+    sim.game_mut().set_cascade_speed(CascadeSpeed::Fast);
+    sim.tap(Buttons::A);
+    assert_eq!(sim.settle(), 4);
+    assert_eq!(sim.state(), MinefieldState::GameOver(true));
+
+    // An instant cascade is done by the time the reveal returns
+    let mut sim = Simulation::with_mines(Vec2::new(6, 1), &mines);
+    sim.game_mut().set_cascade_speed(CascadeSpeed::Instant);
+    assert_eq!(sim.tap(Buttons::A), MinefieldState::GameOver(true));
+    assert_eq!(sim.settle(), 0);
+}
+
+#[test]
+fn input_during_cascade_is_buffered() {
+    // A wall of mines keeps the cascade from winning the game
+    let mines = (0..8).map(|y| Vec2::new(4, y)).collect::<Vec<_>>();
+    let mut sim = Simulation::with_mines(Vec2::new(8, 8), &mines);
+    sim.tap(Buttons::A);
+    assert!(sim.game().board().is_cascading());
+
+    // The board can't change until the cascade is done, but the move isn't lost
+    sim.tap(Buttons::DOWN);
+    sim.tap(Buttons::RIGHT);
+    assert_eq!(sim.game().cursor(), Vec2::new(0, 0));
+    sim.settle();
+    sim.frame(Buttons::NONE);
+    assert_eq!(sim.game().cursor(), Vec2::new(0, 1));
+}
+
+#[test]
+fn chord_reveals_mine_behind_wrong_flag() {
+    let mut sim = Simulation::with_mines(
+        Vec2::new(3, 3),
+        &[Vec2::new(0, 0), Vec2::new(2, 0), Vec2::new(2, 2)],
+    );
+
+    // Reveal the 3 in the middle
+    sim.tap(Buttons::DOWN);
+    sim.tap(Buttons::RIGHT);
+    sim.tap(Buttons::A);
+
+    // Flag the safe block above it and two of the mines
+    let presses = [
+        Buttons::UP,
+        Buttons::R,
+        Buttons::LEFT,
+        Buttons::R,
+        Buttons::RIGHT,
+        Buttons::RIGHT,
+        Buttons::R,
+    ];
+    let script = presses
+        .into_iter()
+        .flat_map(|buttons| [(buttons, 1), (Buttons::NONE, 1)])
+        .collect::<Vec<_>>();
+    assert_eq!(sim.run(&script), MinefieldState::Play);
+    assert_eq!(sim.game().board().flag_count(), 3);
+
+    // Chording the 3 trusts the flags and hits the unflagged mine
+    sim.tap(Buttons::DOWN);
+    sim.tap(Buttons::LEFT);
+    assert_eq!(sim.tap(Buttons::L), MinefieldState::GameOver(false));
+}
+
+#[test]
+fn casual_game_carries_on_after_a_mine() {
+    let mut sim = Simulation::with_mines(Vec2::new(3, 3), &[Vec2::new(0, 0), Vec2::new(1, 0)]);
+    sim.game_mut().board_mut().set_lives_per_game(Some(2));
+
+    // Revealing a mine flags it and the game goes on, one life down
+    assert_eq!(sim.tap(Buttons::A), MinefieldState::Play);
+    assert_eq!(sim.game().board().lives(), Some(1));
+    assert_eq!(
+        sim.game().board().block(Vec2::new(0, 0)),
+        MinefieldBlock::Flag(1)
+    );
+
+    // Until the last life goes on the next one
+    sim.tap(Buttons::RIGHT);
+    assert_eq!(sim.tap(Buttons::A), MinefieldState::GameOver(false));
+}
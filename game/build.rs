@@ -0,0 +1,148 @@
+//! Turns the levels in `tilemap/tilemap.ldtk` into board shapes and puzzles, written to `shapes.rs`
+//! in the build output for `src/shapes.rs` to include.
+//!
+//! Each level is one shape or puzzle, named after the level. Cells of its `Blocks` IntGrid layer
+//! with a value are blocks, and empty cells are holes. A level with any `Mine` blocks is a puzzle,
+//! with its mines and `Open` blocks laid out in advance.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+use serde::Deserialize;
+
+const LDTK_PATH: &str = "tilemap/tilemap.ldtk";
+const LAYER: &str = "Blocks";
+
+// Values of the cells in the Blocks layer
+const HOLE: usize = 0;
+const MINE: usize = 2;
+const OPEN: usize = 3;
+
+// Shape names are shown as a value in the options menu, which fits this many characters
+const MAX_NAME_LEN: usize = 7;
+
+// Puzzles are listed on the level select screen, which fits this many of them with these names
+const MAX_PUZZLES: usize = 12;
+const MAX_PUZZLE_NAME_LEN: usize = 12;
+
+// The minefield background is 64x64 tiles, and each block is 2x2 tiles
+const MAX_SIZE: usize = 32;
+
+fn main() {
+    println!("cargo::rerun-if-changed={LDTK_PATH}");
+
+    let ldtk = fs::read_to_string(LDTK_PATH)
+        .unwrap_or_else(|err| panic!("failed to read {LDTK_PATH}: {err}"));
+    let project: Project = serde_json::from_str(&ldtk)
+        .unwrap_or_else(|err| panic!("{LDTK_PATH} isn't a valid LDtk project: {err}"));
+
+    let mut shapes = vec![];
+    let mut puzzles = vec![];
+    for level in project.levels {
+        let name = level.identifier.to_ascii_uppercase();
+        let layer = level
+            .layer_instances
+            .unwrap_or_else(|| panic!("level {name} is saved outside of {LDTK_PATH}"))
+            .into_iter()
+            .find(|layer| layer.identifier == LAYER)
+            .unwrap_or_else(|| panic!("level {name} has no {LAYER} layer"));
+        let (width, height) = (layer.width, layer.height);
+        assert!(
+            width <= MAX_SIZE && height <= MAX_SIZE,
+            "level {name} is bigger than {MAX_SIZE}x{MAX_SIZE} blocks"
+        );
+
+        let cells = layer.int_grid_csv;
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "level {name} has the wrong number of cells"
+        );
+        assert!(
+            cells.iter().any(|&cell| cell != HOLE),
+            "level {name} has no blocks"
+        );
+
+        let rows: Vec<String> = cells
+            .chunks(width)
+            .map(|row| {
+                row.iter()
+                    .map(|&cell| match cell {
+                        HOLE => '.',
+                        MINE => '*',
+                        OPEN => 'o',
+                        _ => '#',
+                    })
+                    .collect()
+            })
+            .collect();
+        if cells.contains(&MINE) {
+            assert!(
+                name.len() <= MAX_PUZZLE_NAME_LEN,
+                "puzzle {name} needs a name of at most {MAX_PUZZLE_NAME_LEN} characters"
+            );
+            puzzles.push((name, rows));
+        } else {
+            assert!(
+                name.len() <= MAX_NAME_LEN,
+                "level {name} needs a name of at most {MAX_NAME_LEN} characters"
+            );
+            assert!(
+                !cells.contains(&OPEN),
+                "level {name} has open blocks but no mines"
+            );
+            shapes.push((name, rows));
+        }
+    }
+    assert!(
+        puzzles.len() <= MAX_PUZZLES,
+        "there can be at most {MAX_PUZZLES} puzzles"
+    );
+
+    let mut code = String::new();
+    write_levels(&mut code, "SHAPES", "Shape", &shapes);
+    write_levels(&mut code, "PUZZLES", "Puzzle", &puzzles);
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    fs::write(Path::new(&out_dir).join("shapes.rs"), code).expect("failed to write shapes.rs");
+}
+
+/// Write `levels` as a static array called `name` of `kind`, each made with `kind::new`
+fn write_levels(code: &mut String, name: &str, kind: &str, levels: &[(String, Vec<String>)]) {
+    writeln!(code, "pub static {name}: [{kind}; {}] = [", levels.len()).unwrap();
+    for (level_name, rows) in levels {
+        writeln!(code, "    {kind}::new(\"{level_name}\", &[").unwrap();
+        for row in rows {
+            writeln!(code, "        \"{row}\",").unwrap();
+        }
+        writeln!(code, "    ]),").unwrap();
+    }
+    writeln!(code, "];").unwrap();
+}
+
+// The parts of an LDtk project which make up the levels
+
+#[derive(Deserialize)]
+struct Project {
+    levels: Vec<Level>,
+}
+
+#[derive(Deserialize)]
+struct Level {
+    identifier: String,
+    // Left out when the project saves each level to a file of its own
+    #[serde(rename = "layerInstances")]
+    layer_instances: Option<Vec<Layer>>,
+}
+
+#[derive(Deserialize)]
+struct Layer {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__cWid")]
+    width: usize,
+    #[serde(rename = "__cHei")]
+    height: usize,
+    // Empty for layers other than IntGrid layers
+    #[serde(rename = "intGridCsv")]
+    int_grid_csv: Vec<usize>,
+}
@@ -0,0 +1,38 @@
+use agb::input::{Button, ButtonController};
+pub use minesweeper_board::controls::Bindings;
+use minesweeper_board::{Buttons, Input};
+
+// Every button, in the order of their bits
+const BUTTONS: [Button; 10] = [
+    Button::A,
+    Button::B,
+    Button::SELECT,
+    Button::START,
+    Button::RIGHT,
+    Button::LEFT,
+    Button::UP,
+    Button::DOWN,
+    Button::R,
+    Button::L,
+];
+
+/// Read the buttons held this frame and last frame from the button controller, so they can be
+/// passed on to the game logic
+pub fn read_input(button_controller: &ButtonController) -> Input {
+    let mut previous = Buttons::NONE;
+    let mut current = Buttons::NONE;
+    for button in BUTTONS {
+        // Both use the bit layout of the key input register
+        let buttons = Buttons::from_bits_truncate(button.bits() as u16);
+        let is_pressed = button_controller.is_pressed(button);
+        if is_pressed {
+            current |= buttons;
+        }
+        if (is_pressed && !button_controller.is_just_pressed(button))
+            || button_controller.is_just_released(button)
+        {
+            previous |= buttons;
+        }
+    }
+    Input::new(previous, current)
+}
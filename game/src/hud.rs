@@ -0,0 +1,203 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use agb::{
+    display::{
+        GraphicsFrame, Priority,
+        tile_data::TileData,
+        tiled::{RegularBackground, RegularBackgroundSize, TileFormat},
+    },
+    fixnum::vec2,
+};
+use minesweeper_board::{MinefieldBlock, MinefieldItem};
+
+use crate::{text::draw_text, theme::Theme};
+
+// const expressions
+// Columns from the left margin to the edge of the screen, which every counter has to fit in
+const TEXT_WIDTH: usize = 29;
+// Flags and mines are shown with 3 digits at most, like the counter of the classic game
+const MAX_SHOWN_FLAGS: u32 = 999;
+
+/// The floor the cursor is on in a minefield with several, along with a hint of the blocks in line
+/// with it on the floors above and below
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct FloorHint {
+    pub floor: i32,
+    pub floors: i32,
+    pub up: Option<(MinefieldBlock, MinefieldItem)>, // None on the top floor
+    pub down: Option<(MinefieldBlock, MinefieldItem)>, // None on the bottom floor
+}
+
+/// A single character or number for a block seen through a floor, with no glyph for a question
+/// mark so it shows as covered
+fn block_hint(block: Option<(MinefieldBlock, MinefieldItem)>) -> String {
+    match block {
+        None => "-".to_string(),
+        Some((MinefieldBlock::Block | MinefieldBlock::Question, _)) => "+".to_string(),
+        Some((MinefieldBlock::Flag(_), _)) => "!".to_string(),
+        Some((MinefieldBlock::Clear, MinefieldItem::Blank)) => ".".to_string(),
+        Some((MinefieldBlock::Clear, MinefieldItem::Number(n))) => n.to_string(),
+        Some((MinefieldBlock::Clear, MinefieldItem::Mine(_))) => "X".to_string(),
+    }
+}
+
+/// Counters shown along the top of the screen during a game, drawn on their own background
+pub struct Hud {
+    bg: RegularBackground,
+    font: &'static TileData,
+    score: Option<u32>,       // None when the game isn't scored
+    floor: Option<FloorHint>, // None for a minefield with a single floor
+    lives: Option<u32>,       // None for the standard game, which has no lives
+    flags: u32,
+    mines: Option<u32>, // None for an endless minefield, which has no end to its mines
+    message: Option<String>, // shown in place of the counters, such as the result of a game
+}
+
+impl Hud {
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            bg: RegularBackground::new(
+                Priority::P0,
+                RegularBackgroundSize::Background32x32,
+                TileFormat::FourBpp,
+            ),
+            font: theme.gfx().font,
+            score: None,
+            floor: None,
+            lives: None,
+            flags: 0,
+            mines: None,
+            message: None,
+        }
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) -> &mut Self {
+        self.font = theme.gfx().font;
+        self.draw();
+        self
+    }
+
+    /// Show `score`, or nothing for a game which isn't scored
+    pub fn set_score(&mut self, score: Option<u32>) -> &mut Self {
+        if score != self.score {
+            self.score = score;
+            self.draw();
+        }
+        self
+    }
+
+    /// Show which floor the cursor is on and what's above and below it, or nothing for a minefield
+    /// with a single floor
+    pub fn set_floor(&mut self, floor: Option<FloorHint>) -> &mut Self {
+        if floor != self.floor {
+            self.floor = floor;
+            self.draw();
+        }
+        self
+    }
+
+    /// Show how many flags have been placed, out of how many `mines` there are to flag
+    pub fn set_flags(&mut self, flags: u32, mines: Option<u32>) -> &mut Self {
+        if (flags, mines) != (self.flags, self.mines) {
+            self.flags = flags;
+            self.mines = mines;
+            self.draw();
+        }
+        self
+    }
+
+    /// Show `message` in place of the counters, or the counters again for `None`
+    pub fn set_message(&mut self, message: Option<String>) -> &mut Self {
+        if message != self.message {
+            self.message = message;
+            self.draw();
+        }
+        self
+    }
+
+    /// Show how many lives are left, or nothing for the standard game
+    pub fn set_lives(&mut self, lives: Option<u32>) -> &mut Self {
+        if lives != self.lives {
+            self.lives = lives;
+            self.draw();
+        }
+        self
+    }
+
+    /// The message or the counters to show, which only an endless minefield scores and only a
+    /// bounded one has floors, so the score and the floor are never shown together
+    fn text(&self) -> String {
+        if let Some(message) = &self.message {
+            return message.clone();
+        }
+
+        let mut counters = Vec::new();
+        if let Some(score) = self.score {
+            counters.push(format!("SCORE {score}"));
+        }
+        if let Some(floor) = self.floor {
+            counters.push(format!(
+                "F{}/{} U{} D{}",
+                floor.floor + 1,
+                floor.floors,
+                block_hint(floor.up),
+                block_hint(floor.down)
+            ));
+        }
+        let flags = self.flags.min(MAX_SHOWN_FLAGS);
+        match self.mines {
+            Some(mines) => counters.push(format!("!{flags}/{}", mines.min(MAX_SHOWN_FLAGS))),
+            None => counters.push(format!("!{flags}")),
+        }
+        if let Some(lives) = self.lives {
+            counters.push(format!("LIVES {lives}"));
+        }
+        counters.join(" ")
+    }
+
+    fn draw(&mut self) {
+        let text = self.text();
+        debug_assert!(text.len() <= TEXT_WIDTH, "{text} doesn't fit");
+        draw_text(
+            &mut self.bg,
+            vec2(1, 1),
+            &format!("{text:TEXT_WIDTH$}"),
+            self.font,
+        );
+    }
+
+    pub fn show(&self, frame: &mut GraphicsFrame) {
+        self.bg.show(frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use agb::Gba;
+
+    use super::*;
+
+    #[test_case]
+    fn test_longest_counters_fit(_gba: &mut Gba) {
+        let mut hud = Hud::new(Theme::Classic);
+        let hint = Some((MinefieldBlock::Clear, MinefieldItem::Number(72)));
+        hud.set_floor(Some(FloorHint {
+            floor: 1,
+            floors: 3,
+            up: hint,
+            down: hint,
+        }))
+        .set_flags(1234, Some(5678))
+        .set_lives(Some(3));
+        assert_eq!(hud.text(), "F2/3 U72 D72 !999/999 LIVES 3");
+
+        hud.set_floor(None)
+            .set_flags(1234, None)
+            .set_score(Some(u32::MAX));
+        assert!(hud.text().len() <= TEXT_WIDTH);
+    }
+}
@@ -0,0 +1,133 @@
+use agb::{
+    display::{
+        GraphicsFrame, Priority,
+        tile_data::TileData,
+        tiled::{RegularBackground, RegularBackgroundSize, TileFormat},
+    },
+    fixnum::vec2,
+    input::{Button, ButtonController},
+};
+use minesweeper_board::Puzzle;
+
+use crate::{shapes::PUZZLES, text::draw_text, theme::Theme};
+
+// Number of bytes used to store the puzzle progress in save data
+pub const PROGRESS_SIZE: usize = 2;
+
+// const expressions
+const LEVELS_TILE_Y: i32 = 4;
+
+/// Which of the puzzles the player has completed
+#[derive(PartialEq, Eq, Clone, Copy, Default)]
+pub struct Progress {
+    completed: u16, // one bit for each puzzle, in the order they're listed
+}
+
+impl Progress {
+    pub fn is_complete(self, level: usize) -> bool {
+        self.completed & (1 << level) != 0
+    }
+
+    pub fn complete(&mut self, level: usize) {
+        self.completed |= 1 << level;
+    }
+
+    pub fn to_bytes(self) -> [u8; PROGRESS_SIZE] {
+        self.completed.to_le_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8; PROGRESS_SIZE]) -> Self {
+        Self {
+            completed: u16::from_le_bytes(*bytes),
+        }
+    }
+}
+
+/// Where `puzzle` is in the list of puzzles
+pub fn level_of(puzzle: &Puzzle) -> Option<usize> {
+    PUZZLES.iter().position(|p| p == puzzle)
+}
+
+/// Screen for picking which puzzle to play, drawn on its own background
+pub struct LevelSelect {
+    bg: RegularBackground,
+    font: &'static TileData,
+    selected: usize,
+}
+
+impl LevelSelect {
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            bg: RegularBackground::new(
+                Priority::P0,
+                RegularBackgroundSize::Background32x32,
+                TileFormat::FourBpp,
+            ),
+            font: theme.gfx().font,
+            selected: 0,
+        }
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) -> &mut Self {
+        self.font = theme.gfx().font;
+        self
+    }
+
+    fn draw_level(&mut self, level: usize, progress: Progress) {
+        let cursor = if level == self.selected { ">" } else { " " };
+        let tile_pos = vec2(3, LEVELS_TILE_Y + level as i32);
+
+        draw_text(&mut self.bg, tile_pos, cursor, self.font);
+        draw_text(
+            &mut self.bg,
+            tile_pos + vec2(2, 0),
+            PUZZLES[level].name(),
+            self.font,
+        );
+        let done = if progress.is_complete(level) {
+            "DONE"
+        } else {
+            "    "
+        };
+        draw_text(&mut self.bg, tile_pos + vec2(20, 0), done, self.font);
+    }
+
+    pub fn draw(&mut self, progress: Progress) {
+        draw_text(&mut self.bg, vec2(11, 2), "PUZZLES", self.font);
+        for level in 0..PUZZLES.len() {
+            self.draw_level(level, progress);
+        }
+        draw_text(
+            &mut self.bg,
+            vec2(3, 18),
+            "A: PLAY  START: OPTIONS",
+            self.font,
+        );
+    }
+
+    /// Handle player input, returns the puzzle to play once the player has picked one
+    pub fn update(
+        &mut self,
+        button_controller: &ButtonController,
+        progress: Progress,
+    ) -> Option<&'static Puzzle> {
+        if button_controller.is_just_pressed(Button::A) {
+            return Some(&PUZZLES[self.selected]);
+        }
+
+        // Move the selection between puzzles
+        let prev_selected = self.selected;
+        let move_by = button_controller.just_pressed_y_tri() as i32;
+        self.selected = (self.selected as i32 + move_by).rem_euclid(PUZZLES.len() as i32) as usize;
+        if self.selected != prev_selected {
+            self.draw_level(prev_selected, progress);
+            self.draw_level(self.selected, progress);
+        }
+
+        None
+    }
+
+    pub fn show(&self, frame: &mut GraphicsFrame) {
+        self.bg.show(frame);
+    }
+}
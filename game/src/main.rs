@@ -0,0 +1,318 @@
+// Required includes
+#![no_std]
+#![no_main]
+// Test includes
+#![cfg_attr(test, feature(custom_test_frameworks))]
+#![cfg_attr(test, reexport_test_harness_main = "test_main")]
+#![cfg_attr(test, test_runner(agb::test_runner::test_runner))]
+
+mod controls;
+mod hud;
+mod level_select;
+mod minefield;
+mod number_palette;
+mod options;
+mod options_menu;
+mod save;
+mod shapes;
+mod stats;
+mod text;
+mod theme;
+mod types;
+
+extern crate alloc;
+
+use alloc::{format, string::ToString};
+
+use agb::{
+    display::{
+        Priority,
+        tiled::{RegularBackground, RegularBackgroundSize, TileFormat},
+    },
+    fixnum::{Vector2D, vec2},
+    include_wav,
+    input::{Button, ButtonController},
+    sound::mixer::{Frequency, SoundData},
+};
+use agb_tracker::{Track, Tracker, include_xm};
+use hud::Hud;
+use level_select::LevelSelect;
+use minefield::{Minefield, MinefieldState};
+use minesweeper_board::{Shape, Topology};
+use options_menu::OptionsMenu;
+
+// Music and Sound import
+static CURSOR_MOVE: SoundData = include_wav!("sfx/ball-paddle-hit.wav");
+static BGM: Track = include_xm!("sfx/bgm.xm");
+
+// Size of the minefield in blocks when it isn't one of the designed shapes
+const PLAIN_SIZE: Vector2D<i32> = vec2(13, 8);
+
+/// Change the minefield to `shape`, or back to a plain rectangle for `None`
+fn set_shape<'a>(
+    minefield: &'a mut Minefield,
+    bg: &mut RegularBackground,
+    shape: Option<&'static Shape>,
+) -> &'a mut Minefield {
+    match shape {
+        Some(shape) => minefield.set_shape(bg, shape),
+        None => minefield.set_size(bg, PLAIN_SIZE),
+    }
+}
+
+#[agb::entry]
+fn main(mut gba: agb::Gba) -> ! {
+    // Options, restored from save data if there are any
+    gba.save.init_sram();
+    let mut options = save::load_options(&mut gba.save).unwrap_or_else(|_| {
+        agb::println!("Failed to load options, using the defaults");
+        Default::default()
+    });
+    let mut options_menu = OptionsMenu::new(options.theme);
+    let mut options_open = false;
+
+    // Puzzles the player has completed, and the screen for picking one to play
+    let mut progress = save::load_progress(&mut gba.save).unwrap_or_else(|_| {
+        agb::println!("Failed to load puzzle progress, starting from scratch");
+        Default::default()
+    });
+    let mut level_select = LevelSelect::new(options.theme);
+    let mut level_select_open = options.puzzles;
+    if level_select_open {
+        level_select.draw(progress);
+    }
+
+    // Wins and the best endless score, kept across games
+    let mut stats = save::load_stats(&mut gba.save).unwrap_or_else(|_| {
+        agb::println!("Failed to load statistics, starting from scratch");
+        Default::default()
+    });
+
+    // Counters along the top of the screen
+    let mut hud = Hud::new(options.theme);
+
+    // Input manager, responsible for button presses
+    let mut button_controller = ButtonController::new();
+
+    // Background, in the colours of the chosen theme
+    number_palette::load_palettes(options.theme, options.number_palette, options.number_cues);
+
+    // Big enough for a minefield of 32x32 blocks, which scrolls if it doesn't fit on screen
+    let mut bg = RegularBackground::new(
+        Priority::P3,
+        RegularBackgroundSize::Background64x64,
+        TileFormat::FourBpp,
+    );
+
+    // Get the graphics manager, responsible for all the graphics
+    let mut gfx = gba.graphics.get();
+
+    // Sound mixer
+    let mut mixer = gba.mixer.mixer(Frequency::Hz32768);
+
+    // Tracker for BGM
+    let mut tracker = Tracker::new(&BGM);
+
+    // Draw blank block tiles
+    let mut minefield = Minefield::new(PLAIN_SIZE, options.theme, &CURSOR_MOVE);
+    set_shape(&mut minefield, &mut bg, options.shape)
+        .set_options(options)
+        .set_grid(&mut bg, options.grid)
+        .set_topology(&mut bg, options.topology)
+        .set_floors(&mut bg, options.floors())
+        .set_line_clues(&mut bg, options.line_clues)
+        .set_neighbourhood(&mut bg, options.neighbourhood)
+        .set_max_mines_per_block(options.max_mines_per_block())
+        .set_lives_per_game(options.lives_per_game())
+        .reset(&mut bg);
+
+    let mut next_game_state = MinefieldState::Play;
+    let mut prev_game_state = next_game_state;
+    let mut screen_changed;
+
+    loop {
+        // Read buttons
+        button_controller.update();
+        screen_changed = next_game_state != prev_game_state;
+
+        if options_open {
+            let prev_options = options;
+            let menu_open = options_menu.update(&button_controller, &mut options);
+
+            // Change how the game looks straight away, so the player can see the difference
+            if options.theme != prev_options.theme
+                || options.number_palette != prev_options.number_palette
+                || options.number_cues != prev_options.number_cues
+            {
+                number_palette::load_palettes(
+                    options.theme,
+                    options.number_palette,
+                    options.number_cues,
+                );
+            }
+            if options.theme != prev_options.theme {
+                options_menu.set_theme(options.theme).draw(&options);
+                level_select.set_theme(options.theme);
+                hud.set_theme(options.theme);
+                minefield.set_theme(&mut bg, options.theme);
+            }
+            if options.number_cues != prev_options.number_cues {
+                minefield.set_options(options).draw_minefield(&mut bg);
+            }
+
+            // Apply and save the options once the player is done with them
+            if !menu_open {
+                options_open = false;
+                minefield.set_options(options);
+
+                // The clues only add to what's shown, so the game carries on with them
+                if options.line_clues != minefield.line_clues() {
+                    minefield.set_line_clues(&mut bg, options.line_clues);
+                }
+
+                // Puzzles are picked from the level select, and a different board shape needs a new
+                // game since the numbers no longer match
+                if options.puzzles {
+                    level_select_open = true;
+                    level_select.draw(progress);
+                } else if minefield.puzzle().is_some()
+                    || options.shape != minefield.shape()
+                    || options.grid != minefield.grid()
+                    || options.topology != minefield.topology()
+                    || options.neighbourhood != minefield.neighbourhood()
+                    || options.max_mines_per_block() != minefield.max_mines_per_block()
+                    || options.floors() != minefield.floors()
+                    || options.lives_per_game() != minefield.lives_per_game()
+                {
+                    set_shape(&mut minefield, &mut bg, options.shape)
+                        .set_grid(&mut bg, options.grid)
+                        .set_topology(&mut bg, options.topology)
+                        .set_floors(&mut bg, options.floors())
+                        .set_neighbourhood(&mut bg, options.neighbourhood)
+                        .set_max_mines_per_block(options.max_mines_per_block())
+                        .set_lives_per_game(options.lives_per_game())
+                        .reset(&mut bg);
+                    next_game_state = MinefieldState::Play;
+                }
+                if save::save_options(&mut gba.save, &options).is_err() {
+                    agb::println!("Failed to save options");
+                }
+            }
+        } else if level_select_open {
+            if button_controller.is_just_pressed(Button::START) {
+                options_open = true;
+                options_menu.draw(&options);
+            } else if let Some(puzzle) = level_select.update(&button_controller, progress) {
+                level_select_open = false;
+                minefield.set_puzzle(&mut bg, puzzle);
+                next_game_state = MinefieldState::Play;
+            }
+        } else {
+            match next_game_state {
+                // Update the minefield and player cursor and check what the next game screen should be
+                MinefieldState::Play => {
+                    if button_controller.is_just_pressed(Button::START) {
+                        // A puzzle is left for the level select, which leads on to the options
+                        if minefield.puzzle().is_some() {
+                            level_select_open = true;
+                            level_select.draw(progress);
+                        } else {
+                            options_open = true;
+                            options_menu.draw(&options);
+                        }
+                    } else {
+                        next_game_state = minefield.update(&mut bg, &button_controller, &mut mixer);
+                    }
+                }
+
+                // Handle game over screen
+                MinefieldState::GameOver(is_win) => {
+                    // Flag the remaining mines on a win, otherwise reveal all blocks
+                    if prev_game_state == MinefieldState::Play {
+                        if is_win {
+                            // Counted before the remaining mines are flagged for the player
+                            let flag_count = minefield.flag_count();
+                            stats.record_win(flag_count);
+                            hud.set_message(Some(if flag_count == 0 {
+                                format!("NO-FLAG WIN! {} SO FAR", stats.no_flag_wins)
+                            } else {
+                                format!("YOU WIN! {} WINS", stats.wins)
+                            }));
+                            minefield.flag_remaining_mines(&mut bg);
+
+                            // Remember which puzzles have been solved
+                            if let Some(level) = minefield.puzzle().and_then(level_select::level_of)
+                            {
+                                progress.complete(level);
+                                if save::save_progress(&mut gba.save, &progress).is_err() {
+                                    agb::println!("Failed to save puzzle progress");
+                                }
+                            }
+                        } else if minefield.topology() == Topology::Endless {
+                            let score = minefield.cleared_blocks();
+                            hud.set_message(Some(if stats.record_score(score) {
+                                format!("NEW BEST! SCORE {score}")
+                            } else {
+                                format!("SCORE {score} BEST {}", stats.best_score)
+                            }));
+                            minefield.reveal(&mut bg);
+                        } else {
+                            hud.set_message(Some("GAME OVER".to_string()));
+                            minefield.reveal(&mut bg);
+                        }
+                        if save::save_stats(&mut gba.save, &stats).is_err() {
+                            agb::println!("Failed to save statistics");
+                        }
+                    }
+
+                    // Ask player for start input, going back to the level select after solving a
+                    // puzzle and trying it again otherwise
+                    if button_controller.is_just_pressed(Button::START) {
+                        if is_win && minefield.puzzle().is_some() {
+                            level_select_open = true;
+                            level_select.draw(progress);
+                        } else {
+                            minefield.reset(&mut bg);
+                            next_game_state = MinefieldState::Play;
+                        }
+                    }
+                }
+            }
+
+            // An endless minefield is scored by how much of it the player clears, one with floors
+            // shows which of them the cursor is on, and a casual game counts down its lives
+            let endless = minefield.topology() == Topology::Endless;
+            hud.set_score(endless.then(|| minefield.cleared_blocks()))
+                .set_floor(minefield.floor_hint())
+                .set_flags(minefield.flag_count(), minefield.mine_total())
+                .set_lives(minefield.lives());
+            if next_game_state == MinefieldState::Play {
+                hud.set_message(None);
+            }
+        }
+
+        // Prepare the frame
+        let mut frame = gfx.frame();
+
+        if options_open {
+            options_menu.show(&mut frame);
+        } else if level_select_open {
+            level_select.show(&mut frame);
+        } else {
+            hud.show(&mut frame);
+            bg.show(&mut frame);
+            if next_game_state == MinefieldState::Play {
+                minefield.show(&mut frame);
+            }
+        }
+        tracker.step(&mut mixer);
+        mixer.frame();
+        frame.commit();
+
+        // make the random number generator harder to predict
+        let _ = agb::rng::next_i32();
+        if screen_changed {
+            prev_game_state = next_game_state;
+        }
+    }
+}
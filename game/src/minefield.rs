@@ -0,0 +1,1323 @@
+pub mod clues;
+pub mod layout;
+pub mod player_cursor;
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use agb::{
+    display::{
+        GraphicsFrame,
+        tile_data::TileData,
+        tiled::{RegularBackground, TileSetting},
+    },
+    fixnum::{Vector2D, vec2},
+    input::ButtonController,
+    sound::mixer::{Mixer, SoundData},
+};
+pub use minesweeper_board::MinefieldState;
+use minesweeper_board::{
+    Game, Grid, Input, MinefieldBlock, MinefieldItem, Neighbourhood, Puzzle, Renderer, Shape,
+    Topology, Vec2,
+};
+use player_cursor::PlayerCursor;
+
+use crate::{
+    controls,
+    hud::FloorHint,
+    number_palette::{MAX_NUMBER, cued_number_tiles, extended_number_tiles, number_palette_id},
+    options::Options,
+    text::draw_text,
+    theme::Theme,
+    types::Fixed,
+};
+
+// Pixels a new floor slides in from when changing floors, and how far it goes each frame
+const FLOOR_SLIDE_DISTANCE: i32 = layout::BLOCK_SIZE;
+const FLOOR_SLIDE_SPEED: i32 = 2;
+
+pub struct BlockIndices {
+    indices: [usize; 4],
+}
+
+// Tiles for a covered block which is being pressed
+const PRESSED_BLOCK_INDICES: BlockIndices = BlockIndices {
+    indices: [12, 13, 14, 15],
+};
+
+/// Which tiles to use when drawing part of the board
+trait BlockTiles {
+    fn get_block_indices(&self) -> BlockIndices;
+}
+
+impl BlockTiles for MinefieldBlock {
+    fn get_block_indices(&self) -> BlockIndices {
+        use MinefieldBlock::*;
+        match *self {
+            Clear => BlockIndices {
+                indices: [0, 1, 2, 3],
+            },
+            Block => BlockIndices {
+                indices: [0, 1, 2, 3],
+            },
+            Flag(1) => BlockIndices {
+                indices: [4, 5, 6, 7],
+            },
+            // Blocks with more flags follow the pressed block
+            Flag(2) => BlockIndices {
+                indices: [16, 17, 18, 19],
+            },
+            Flag(_) => BlockIndices {
+                indices: [20, 21, 22, 23],
+            },
+            Question => BlockIndices {
+                indices: [8, 9, 10, 11],
+            },
+        }
+    }
+}
+
+impl BlockTiles for MinefieldItem {
+    fn get_block_indices(&self) -> BlockIndices {
+        use MinefieldItem::*;
+        match *self {
+            Blank => BlockIndices {
+                indices: [0, 1, 2, 3],
+            },
+            Number(n) => {
+                // Numbers above 8 start again from the top of the extended number tiles, which
+                // `Options::fits_numbers` keeps every game within
+                debug_assert!(n <= MAX_NUMBER);
+                let first = if n > 8 { 9 } else { 1 };
+                let offset = (n - first) as usize * 4;
+                BlockIndices {
+                    indices: [offset, 1 + offset, 2 + offset, 3 + offset],
+                }
+            }
+            Mine(_) => BlockIndices {
+                indices: [32, 33, 34, 35],
+            },
+        }
+    }
+}
+
+/// Tile position of the top left of a block, since each block is 2x2 tiles
+fn block_tile_pos(grid: Grid, block_pos: Vec2) -> Vector2D<i32> {
+    // Odd rows of a hex grid are shifted right by half a block
+    let row_offset = (grid == Grid::Hex && block_pos.y % 2 != 0) as i32;
+    vec2(block_pos.x * 2 + row_offset, block_pos.y * 2)
+}
+
+fn draw_block(
+    bg: &mut RegularBackground,
+    tile_pos: Vector2D<i32>,
+    tile_data: &TileData,
+    tile_indices: BlockIndices,
+    palette_id: Option<u8>, // draw with this palette instead of the tiles' own
+) {
+    for y in 0..2 {
+        for x in 0..2 {
+            // Index alternates between 0/1 for even rows
+            // and 2/3 for odd rows, forming a 16x16 block
+            let tile_index = (x + (y * 2)) as usize;
+            let mut tile_setting = tile_data.tile_settings[tile_indices.indices[tile_index]];
+            if let Some(palette_id) = palette_id {
+                tile_setting = tile_setting.palette(palette_id);
+            }
+            bg.set_tile(
+                (tile_pos.x + x, tile_pos.y + y),
+                &tile_data.tiles,
+                tile_setting,
+            );
+        }
+    }
+}
+
+fn clear_block(bg: &mut RegularBackground, tile_pos: Vector2D<i32>, tile_data: &TileData) {
+    for y in 0..2 {
+        for x in 0..2 {
+            bg.set_tile(
+                (tile_pos.x + x, tile_pos.y + y),
+                &tile_data.tiles,
+                TileSetting::BLANK,
+            );
+        }
+    }
+}
+
+fn draw_cleared_block(
+    bg: &mut RegularBackground,
+    tile_pos: Vector2D<i32>,
+    item: MinefieldItem,
+    bg_blocks: &TileData,
+    bg_numbers: &TileData,
+    bg_digits: &TileData,
+) {
+    clear_block(bg, tile_pos, bg_blocks);
+
+    // Draw the item, numbers get a palette each so that their colours can be changed
+    match item {
+        MinefieldItem::Blank => (),
+        MinefieldItem::Number(n) => draw_block(
+            bg,
+            tile_pos,
+            if n > 8 {
+                extended_number_tiles()
+            } else {
+                bg_digits
+            },
+            item.get_block_indices(),
+            Some(number_palette_id(n)),
+        ),
+        MinefieldItem::Mine(_) => {
+            draw_block(bg, tile_pos, bg_numbers, item.get_block_indices(), None)
+        }
+    }
+}
+
+/// Whether `block_pos` is in `view`, given as the first block and how many across and down
+fn in_view((first, size): (Vec2, Vec2), block_pos: Vec2) -> bool {
+    block_pos.x >= first.x
+        && block_pos.y >= first.y
+        && block_pos.x < first.x + size.x
+        && block_pos.y < first.y + size.y
+}
+
+/// Every block in `view`, row by row
+fn view_blocks((first, size): (Vec2, Vec2)) -> impl Iterator<Item = Vec2> {
+    (first.y..first.y + size.y)
+        .flat_map(move |y| (first.x..first.x + size.x).map(move |x| Vec2::new(x, y)))
+}
+
+/// What a block was drawn as, which the tests check since the background can't be read back
+#[cfg(test)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Drawn {
+    Block(MinefieldBlock),
+    Cleared(MinefieldItem),
+    Pressed,
+}
+
+/// Draws the game onto a background as it changes
+struct BackgroundRenderer<'bg> {
+    bg: &'bg mut RegularBackground,
+    grid: Grid,
+    // The blocks which can be drawn, either those on screen for an endless minefield which wraps
+    // around the background or those on the floor being shown
+    view: Option<(Vec2, Vec2)>,
+    floor_origin: Vec2, // top left block of the floor being shown, which every floor is drawn from
+    bg_blocks: &'static TileData, // square or hex blocks to suit the grid
+    bg_numbers: &'static TileData,
+    bg_digits: &'static TileData, // the numbers 1 to 8, which may be from different tiles
+    cursor_move: Option<(Vec2, bool)>, // where the cursor moved to and whether it wrapped
+    // Rows and columns with a covered block drawn in them, whose clues may have changed
+    changed_rows: Vec<i32>,
+    changed_columns: Vec<i32>,
+    #[cfg(test)]
+    drawn: Vec<(Vec2, Drawn)>,
+}
+
+impl BackgroundRenderer<'_> {
+    /// Whether `block_pos` can be drawn without landing on top of a block which is on screen
+    fn can_draw(&self, block_pos: Vec2) -> bool {
+        self.view.is_none_or(|view| in_view(view, block_pos))
+    }
+
+    fn tile_pos(&self, block_pos: Vec2) -> Vector2D<i32> {
+        block_tile_pos(self.grid, block_pos - self.floor_origin)
+    }
+
+    fn line_changed(&mut self, block_pos: Vec2) {
+        if !self.changed_rows.contains(&block_pos.y) {
+            self.changed_rows.push(block_pos.y);
+        }
+        if !self.changed_columns.contains(&block_pos.x) {
+            self.changed_columns.push(block_pos.x);
+        }
+    }
+}
+
+impl Renderer for BackgroundRenderer<'_> {
+    fn draw_block(&mut self, block_pos: Vec2, block: MinefieldBlock) {
+        if !self.can_draw(block_pos) {
+            return;
+        }
+        draw_block(
+            self.bg,
+            self.tile_pos(block_pos),
+            self.bg_blocks,
+            block.get_block_indices(),
+            None,
+        );
+        #[cfg(test)]
+        self.drawn.push((block_pos, Drawn::Block(block)));
+
+        // Flags are only ever drawn as covered blocks, so these are the only lines they change
+        self.line_changed(block_pos);
+    }
+
+    fn draw_cleared_block(&mut self, block_pos: Vec2, item: MinefieldItem) {
+        if !self.can_draw(block_pos) {
+            return;
+        }
+        draw_cleared_block(
+            self.bg,
+            self.tile_pos(block_pos),
+            item,
+            self.bg_blocks,
+            self.bg_numbers,
+            self.bg_digits,
+        );
+        #[cfg(test)]
+        self.drawn.push((block_pos, Drawn::Cleared(item)));
+    }
+
+    fn draw_pressed_block(&mut self, block_pos: Vec2) {
+        if !self.can_draw(block_pos) {
+            return;
+        }
+        draw_block(
+            self.bg,
+            self.tile_pos(block_pos),
+            self.bg_blocks,
+            PRESSED_BLOCK_INDICES,
+            None,
+        );
+        #[cfg(test)]
+        self.drawn.push((block_pos, Drawn::Pressed));
+    }
+
+    fn move_cursor(&mut self, block_pos: Vec2, wrapped: bool) {
+        // The cursor sprite is moved once the frame's update is done, since it needs the mixer
+        self.cursor_move = Some((block_pos, wrapped));
+    }
+
+    fn view(&self) -> Option<(Vec2, Vec2)> {
+        self.view
+    }
+}
+
+pub struct Minefield {
+    pos: Vector2D<Fixed>,
+    bg_blocks: &'static TileData,
+    bg_hex_blocks: &'static TileData,
+    bg_numbers: &'static TileData,
+    font: &'static TileData, // for the markers around the edges of a wrapping minefield
+    bg_clues: &'static TileData,
+    number_cues: bool, // draw the numbers with marks to tell the 7 and 8 apart
+    line_clues: bool,  // show the mines in each row and column beside them
+    shape: Option<&'static Shape>, // None for a plain rectangle
+    puzzle: Option<&'static Puzzle>, // None for random mines
+    floor_slide: i32,  // pixels the floor being shown is still to slide after changing floors
+    game: Game,
+    cursor: PlayerCursor,
+    // Every block drawn by `update`, oldest first
+    #[cfg(test)]
+    drawn: Vec<(Vec2, Drawn)>,
+}
+
+impl Minefield {
+    /// Create a minefield with block `size` (w x h), laid out on screen to suit its size, drawn in
+    /// `theme`
+    pub fn new(size: Vector2D<i32>, theme: Theme, sound_cursor_move: &'static SoundData) -> Self {
+        let gfx = theme.gfx();
+        let pos =
+            layout::board_pos(size * layout::BLOCK_SIZE, vec2(0, 0), vec2(0, 0)).change_base();
+        Self {
+            pos,
+            bg_blocks: gfx.blocks,
+            bg_hex_blocks: gfx.hex_blocks,
+            bg_numbers: gfx.numbers,
+            font: gfx.font,
+            bg_clues: gfx.clues,
+            number_cues: false,
+            line_clues: false,
+            shape: None,
+            puzzle: None,
+            floor_slide: 0,
+            game: Game::new(Vec2::new(size.x, size.y)),
+            cursor: PlayerCursor::new(pos, gfx.cursor, sound_cursor_move),
+            #[cfg(test)]
+            drawn: Vec::new(),
+        }
+    }
+
+    /// Switch to the art for `theme`, drawing the minefield again with it
+    pub fn set_theme(&mut self, bg: &mut RegularBackground, theme: Theme) -> &mut Self {
+        let gfx = theme.gfx();
+        self.bg_blocks = gfx.blocks;
+        self.bg_hex_blocks = gfx.hex_blocks;
+        self.bg_numbers = gfx.numbers;
+        self.font = gfx.font;
+        self.bg_clues = gfx.clues;
+        self.cursor.set_sprite(gfx.cursor);
+        self.draw_minefield(bg);
+        self
+    }
+
+    /// Resize the minefield and lay it out again, ready for a [`Minefield::reset`]
+    pub fn set_size(&mut self, bg: &mut RegularBackground, size: Vector2D<i32>) -> &mut Self {
+        self.clear_minefield(bg);
+        self.game.set_size(Vec2::new(size.x, size.y));
+        self.shape = None;
+        self.puzzle = None;
+        self.lay_out(bg);
+        self
+    }
+
+    pub fn shape(&self) -> Option<&'static Shape> {
+        self.shape
+    }
+
+    /// Change the minefield to `shape` and lay it out again, ready for a [`Minefield::reset`]
+    ///
+    /// The holes in the shape are left blank.
+    pub fn set_shape(&mut self, bg: &mut RegularBackground, shape: &'static Shape) -> &mut Self {
+        self.clear_minefield(bg);
+        self.game.set_shape(shape);
+        self.shape = Some(shape);
+        self.puzzle = None;
+        self.lay_out(bg);
+        self
+    }
+
+    pub fn puzzle(&self) -> Option<&'static Puzzle> {
+        self.puzzle
+    }
+
+    /// Start `puzzle`, laying out and drawing the minefield for it
+    ///
+    /// A puzzle is always played on a square grid with solid edges and the usual neighbours, and
+    /// [`Minefield::reset`] starts it again from the beginning.
+    pub fn set_puzzle(&mut self, bg: &mut RegularBackground, puzzle: &'static Puzzle) -> &mut Self {
+        self.clear_minefield(bg);
+        self.game.set_puzzle(puzzle);
+        self.shape = None;
+        self.puzzle = Some(puzzle);
+        self.lay_out(bg);
+        self.draw_minefield(bg);
+        self
+    }
+
+    /// Arrange the blocks in `grid`, drawing the minefield again laid out for it
+    pub fn set_grid(&mut self, bg: &mut RegularBackground, grid: Grid) -> &mut Self {
+        self.clear_minefield(bg);
+        self.game.board_mut().set_grid(grid);
+        self.lay_out(bg);
+        self.draw_minefield(bg);
+        self
+    }
+
+    /// Join up the edges of the minefield as in `topology`, drawing it again with the new numbers
+    ///
+    /// An endless minefield is laid out around the cursor, ready for a [`Minefield::reset`].
+    pub fn set_topology(&mut self, bg: &mut RegularBackground, topology: Topology) -> &mut Self {
+        self.clear_minefield(bg);
+        self.game.set_topology(topology);
+        self.lay_out(bg);
+        self.draw_minefield(bg);
+        self
+    }
+
+    pub fn floors(&self) -> i32 {
+        self.game.board().floors()
+    }
+
+    /// Stack the minefield into `floors` floors and lay it out again, ready for a
+    /// [`Minefield::reset`]
+    ///
+    /// Only the floor with the cursor on it is shown, with L and R going up and down floors
+    /// while the jump button is held.
+    pub fn set_floors(&mut self, bg: &mut RegularBackground, floors: i32) -> &mut Self {
+        self.clear_minefield(bg);
+        self.game.set_floors(floors);
+        self.lay_out(bg);
+        self
+    }
+
+    /// The top left block of the floor the cursor is on
+    fn floor_origin(&self) -> Vec2 {
+        let board = self.game.board();
+        board.floor_origin(board.floor_of(self.game.cursor()))
+    }
+
+    /// Which floor the cursor is on, and what's in line with it on the floors above and below, or
+    /// `None` for a minefield with a single floor
+    pub fn floor_hint(&self) -> Option<FloorHint> {
+        let board = self.game.board();
+        if board.floors() == 1 {
+            return None;
+        }
+        let cursor = self.game.cursor();
+        let block_hint = |step| {
+            board.floor_step(cursor, step).map(|block_pos| {
+                (
+                    board.block(block_pos),
+                    board.determine_minefield_item(block_pos),
+                )
+            })
+        };
+        Some(FloorHint {
+            floor: board.floor_of(cursor),
+            floors: board.floors(),
+            up: block_hint(-1),
+            down: block_hint(1),
+        })
+    }
+
+    /// Blank every block, since a smaller or differently arranged minefield won't draw over them
+    fn clear_minefield(&self, bg: &mut RegularBackground) {
+        // An endless minefield could have been drawn anywhere on the background, which is 64x64
+        // tiles
+        if self.topology() == Topology::Endless {
+            for y in (0..64).step_by(2) {
+                for x in (0..64).step_by(2) {
+                    clear_block(bg, vec2(x, y), self.bg_blocks);
+                }
+            }
+            return;
+        }
+
+        self.draw_edge_markers(bg, false);
+        self.draw_clues(bg, false);
+        let board = self.game.board();
+        let size = board.size();
+        for y in 0..size.y {
+            for x in 0..size.x {
+                clear_block(
+                    bg,
+                    block_tile_pos(board.grid(), Vec2::new(x, y)),
+                    self.bg_blocks,
+                );
+            }
+        }
+    }
+
+    /// Size of the minefield in tiles, with the odd rows of a hex grid sticking out
+    fn tile_size(&self) -> Vector2D<i32> {
+        let board = self.game.board();
+        let size = board.size();
+        let row_offset = (board.grid() == Grid::Hex && size.y > 1) as i32;
+        vec2(size.x * 2 + row_offset, size.y * 2)
+    }
+
+    /// Draw arrows beside and dashes above and below the minefield to show that its edges wrap,
+    /// or blank them if `show` is false
+    ///
+    /// The dashes are left out for a hex grid whose top and bottom don't join up.
+    fn draw_edge_markers(&self, bg: &mut RegularBackground, show: bool) {
+        let (left, right) = if show { ("<", ">") } else { (" ", " ") };
+        let top = if show && self.game.board().wraps_vertically() {
+            "-"
+        } else {
+            " "
+        };
+        let tile_size = self.tile_size();
+        for y in (0..tile_size.y).step_by(2) {
+            draw_text(bg, vec2(-1, y), left, self.font);
+            draw_text(bg, vec2(tile_size.x, y + 1), right, self.font);
+        }
+        for x in (0..tile_size.x).step_by(2) {
+            draw_text(bg, vec2(x, -1), top, self.font);
+            draw_text(bg, vec2(x + 1, tile_size.y), top, self.font);
+        }
+    }
+
+    pub fn line_clues(&self) -> bool {
+        self.line_clues
+    }
+
+    /// Show how many mines are in each row and column beside them, laying the minefield out again
+    /// to make room
+    ///
+    /// Only a minefield with solid edges and a single floor has them, since the edges of the others
+    /// are in the way and the floors would need a clue for each of them.
+    pub fn set_line_clues(&mut self, bg: &mut RegularBackground, line_clues: bool) -> &mut Self {
+        self.clear_minefield(bg);
+        self.line_clues = line_clues;
+        self.lay_out(bg);
+        self.draw_minefield(bg);
+        self
+    }
+
+    fn shows_clues(&self) -> bool {
+        self.line_clues && self.topology() == Topology::Bounded && self.floors() == 1
+    }
+
+    /// Draw the clues beside each row and above each column, or blank them if `show` is false
+    fn draw_clues(&self, bg: &mut RegularBackground, show: bool) {
+        let size = self.game.board().size();
+        for y in 0..size.y {
+            self.draw_row_clue(bg, y, show);
+        }
+        for x in 0..size.x {
+            self.draw_column_clue(bg, x, show);
+        }
+    }
+
+    /// Draw the clue for row `y` in the block to the left of the minefield
+    fn draw_row_clue(&self, bg: &mut RegularBackground, y: i32, show: bool) {
+        let clue = show.then(|| self.game.board().row_clue(y));
+        clues::draw_clue(bg, vec2(-2, y * 2), clue, self.bg_clues, true);
+    }
+
+    /// Draw the clue for column `x` in the row of tiles above the minefield
+    fn draw_column_clue(&self, bg: &mut RegularBackground, x: i32, show: bool) {
+        let clue = show.then(|| self.game.board().column_clue(x));
+        clues::draw_clue(bg, vec2(x * 2, -1), clue, self.bg_clues, false);
+    }
+
+    /// Move the minefield to where the layout puts it, with the cursor sprite over its block
+    ///
+    /// An endless minefield goes back to having the cursor in the middle of the screen.
+    fn lay_out(&mut self, bg: &mut RegularBackground) {
+        let pos = match self.topology() {
+            Topology::Endless => {
+                let cursor = self.block_pixel_offset(self.game.cursor());
+                layout::endless_pos(cursor, None).change_base()
+            }
+            _ => self.layout_pos(),
+        };
+        self.set_pos(bg, pos);
+        self.cursor
+            .set_pos(self.block_to_pixel_pos(self.game.cursor()));
+    }
+
+    /// Where the layout puts the minefield, keeping the cursor in view
+    fn layout_pos(&self) -> Vector2D<Fixed> {
+        let cursor = self.block_pixel_offset(self.game.cursor());
+        if self.topology() == Topology::Endless {
+            return layout::endless_pos(cursor, Some(self.pos.round())).change_base();
+        }
+        let pixel_size = self.tile_size() * 8;
+
+        // The clues beside the rows take up a block to the left of the minefield, while the ones
+        // above the columns fit in beside the HUD
+        let margin = if self.shows_clues() {
+            vec2(layout::BLOCK_SIZE, 0)
+        } else {
+            vec2(0, 0)
+        };
+        let pos = layout::board_pos(
+            pixel_size + margin,
+            cursor + margin,
+            self.pos.round() - margin,
+        );
+        (pos + margin).change_base()
+    }
+
+    /// The blocks of an endless minefield which are on screen, or of the floor being shown, as the
+    /// first block and how many across and down
+    ///
+    /// The background only has room for 32x32 blocks, so an endless minefield wraps around it and
+    /// only what's on screen is drawn. Each floor is drawn in the same place as the others.
+    fn view(&self) -> Option<(Vec2, Vec2)> {
+        if self.floors() > 1 {
+            return Some((self.floor_origin(), self.game.board().size()));
+        }
+        if self.topology() != Topology::Endless {
+            return None;
+        }
+        let (first, size) = layout::visible_blocks(self.pos.round());
+
+        // The odd rows of a hex grid stick out by half a block, so the block before them shows
+        let row_offset = (self.grid() == Grid::Hex) as i32;
+        Some((
+            Vec2::new(first.x - row_offset, first.y),
+            Vec2::new(size.x + row_offset, size.y),
+        ))
+    }
+
+    pub fn set_pos(&mut self, bg: &mut RegularBackground, pos: Vector2D<Fixed>) -> &mut Self {
+        // Move the minefield and adjust the cursor accordingly
+        let prev_pos = self.pos;
+        self.pos = pos;
+
+        // Set the pos of the minefield
+        let pixel_pos = pos.round();
+        bg.set_scroll_pos((-pixel_pos.x, -pixel_pos.y));
+
+        // Set the pos of the cursor
+        self.cursor.set_pos(self.cursor.pos + (pos - prev_pos));
+        self
+    }
+
+    pub fn set_options(&mut self, options: Options) -> &mut Self {
+        self.game
+            .set_cursor_wrap(options.cursor_wrap)
+            .set_cascade_speed(options.cascade_speed);
+        self.game
+            .board_mut()
+            .set_question_marks(options.question_marks);
+        self.game
+            .controls_mut()
+            .set_bindings(options.bindings)
+            .set_reveal_on_press(options.reveal_on_press);
+        self.number_cues = options.number_cues;
+        self
+    }
+
+    pub fn grid(&self) -> Grid {
+        self.game.board().grid()
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.game.board().topology()
+    }
+
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        self.game.board().neighbourhood()
+    }
+
+    /// Count the mines in `neighbourhood`, drawing the minefield again with the new numbers
+    pub fn set_neighbourhood(
+        &mut self,
+        bg: &mut RegularBackground,
+        neighbourhood: Neighbourhood,
+    ) -> &mut Self {
+        self.game.board_mut().set_neighbourhood(neighbourhood);
+        self.draw_minefield(bg);
+        self
+    }
+
+    pub fn max_mines_per_block(&self) -> u32 {
+        self.game.board().max_mines_per_block()
+    }
+
+    /// Let new games put up to `max_mines_per_block` mines in a block
+    pub fn set_max_mines_per_block(&mut self, max_mines_per_block: u32) -> &mut Self {
+        self.game
+            .board_mut()
+            .set_max_mines_per_block(max_mines_per_block);
+        self
+    }
+
+    pub fn lives_per_game(&self) -> Option<u32> {
+        self.game.board().lives_per_game()
+    }
+
+    /// Start new games with `lives_per_game` lives for revealing mines, or `None` for the standard
+    /// game
+    pub fn set_lives_per_game(&mut self, lives_per_game: Option<u32>) -> &mut Self {
+        self.game.board_mut().set_lives_per_game(lives_per_game);
+        self
+    }
+
+    /// Lives left in this game, or `None` for the standard game
+    pub fn lives(&self) -> Option<u32> {
+        self.game.board().lives()
+    }
+
+    pub fn flag_count(&self) -> u32 {
+        self.game.board().flag_count()
+    }
+
+    /// How many mines there are to flag, or `None` on an endless minefield
+    pub fn mine_total(&self) -> Option<u32> {
+        self.game.board().mine_total()
+    }
+
+    /// Safe blocks cleared so far, which is the score on an endless minefield
+    pub fn cleared_blocks(&self) -> u32 {
+        self.game.board().cleared_blocks()
+    }
+
+    fn renderer<'bg>(&self, bg: &'bg mut RegularBackground) -> BackgroundRenderer<'bg> {
+        let grid = self.game.board().grid();
+        BackgroundRenderer {
+            bg,
+            grid,
+            view: self.view(),
+            floor_origin: self.floor_origin(),
+            bg_blocks: match grid {
+                Grid::Square => self.bg_blocks,
+                Grid::Hex => self.bg_hex_blocks,
+            },
+            bg_numbers: self.bg_numbers,
+            bg_digits: if self.number_cues {
+                cued_number_tiles()
+            } else {
+                self.bg_numbers
+            },
+            cursor_move: None,
+            changed_rows: Vec::new(),
+            changed_columns: Vec::new(),
+            #[cfg(test)]
+            drawn: Vec::new(),
+        }
+    }
+
+    pub fn draw_minefield(&self, bg: &mut RegularBackground) {
+        // Draw all the blocks based on what's contained in the board
+        self.game.draw(&mut self.renderer(bg));
+        match self.topology() {
+            Topology::Endless => (),
+            topology => self.draw_edge_markers(bg, topology == Topology::Toroidal),
+        }
+        if self.shows_clues() {
+            self.draw_clues(bg, true);
+        }
+
+        // Scroll the background to take into account off-tile position
+        let pos = self.pos.round();
+        bg.set_scroll_pos((-pos.x, -pos.y));
+    }
+
+    /// Pixel position of a block on the floor being shown relative to the top left of the
+    /// minefield
+    fn block_pixel_offset(&self, block_pos: Vec2) -> Vector2D<i32> {
+        block_tile_pos(self.game.board().grid(), block_pos - self.floor_origin()) * 8
+    }
+
+    fn block_to_pixel_pos(&self, block_pos: Vec2) -> Vector2D<Fixed> {
+        self.pos + self.block_pixel_offset(block_pos).change_base()
+    }
+
+    pub fn update(
+        &mut self,
+        bg: &mut RegularBackground,
+        button_controller: &ButtonController,
+        mixer: &mut Mixer,
+    ) -> MinefieldState {
+        let input = controls::read_input(button_controller);
+        self.update_with_input(bg, &input, mixer)
+    }
+
+    fn update_with_input(
+        &mut self,
+        bg: &mut RegularBackground,
+        input: &Input,
+        mixer: &mut Mixer,
+    ) -> MinefieldState {
+        let prev_floor_origin = self.floor_origin();
+        let mut renderer = self.renderer(bg);
+        let state = self.game.update(input, &mut renderer);
+        let BackgroundRenderer {
+            cursor_move,
+            changed_rows,
+            changed_columns,
+            #[cfg(test)]
+            drawn,
+            ..
+        } = renderer;
+        #[cfg(test)]
+        self.drawn.extend(drawn);
+
+        // A chord, or a mine in a casual game, can flag blocks away from the cursor as well
+        if self.shows_clues() {
+            for y in changed_rows {
+                self.draw_row_clue(bg, y, true);
+            }
+            for x in changed_columns {
+                self.draw_column_clue(bg, x, true);
+            }
+        }
+
+        // Move the cursor sprite to wherever the game moved the cursor
+        if let Some((block_pos, wrapped)) = cursor_move {
+            let pixel_pos = self.block_to_pixel_pos(block_pos);
+            if wrapped {
+                self.cursor.wrap_to(pixel_pos, mixer);
+            } else {
+                self.cursor.move_by(pixel_pos - self.cursor.pos, mixer);
+            }
+
+            // Scroll a minefield which doesn't fit on screen to keep the cursor in view
+            let pos = self.layout_pos();
+            if pos != self.pos {
+                let prev_view = self.view();
+                self.set_pos(bg, pos);
+
+                // Only what's on screen of an endless minefield is drawn, so draw what scrolled in
+                if let (Some(prev_view), Some(view)) = (prev_view, self.view()) {
+                    let scrolled_in =
+                        view_blocks(view).filter(|&block_pos| !in_view(prev_view, block_pos));
+                    self.game.draw_blocks(&mut self.renderer(bg), scrolled_in);
+                }
+            }
+        }
+
+        // Draw the floor the cursor went to over the one it left, sliding it in from the way the
+        // cursor went
+        let floor_origin = self.floor_origin();
+        if floor_origin != prev_floor_origin {
+            self.draw_minefield(bg);
+            self.floor_slide =
+                (floor_origin.y - prev_floor_origin.y).signum() * FLOOR_SLIDE_DISTANCE;
+        }
+        if self.floor_slide != 0 {
+            self.floor_slide -= self.floor_slide.signum() * FLOOR_SLIDE_SPEED;
+            let pos = self.pos.round() + vec2(0, self.floor_slide);
+            bg.set_scroll_pos((-pos.x, -pos.y));
+        }
+
+        state
+    }
+
+    pub fn reveal(&mut self, bg: &mut RegularBackground) {
+        let mut renderer = self.renderer(bg);
+        self.game.reveal(&mut renderer);
+        if self.shows_clues() {
+            self.draw_clues(bg, true);
+        }
+    }
+
+    pub fn flag_remaining_mines(&mut self, bg: &mut RegularBackground) {
+        let mut renderer = self.renderer(bg);
+        self.game.flag_remaining_mines(&mut renderer);
+        if self.shows_clues() {
+            self.draw_clues(bg, true);
+        }
+    }
+
+    pub fn reset(&mut self, bg: &mut RegularBackground) {
+        // Reset all blocks and regenerate mines, or lay the puzzle out again
+        match self.puzzle {
+            Some(puzzle) => {
+                self.game.set_puzzle(puzzle);
+            }
+            None => self.game.reset(agb::rng::next_i32),
+        }
+
+        // An endless minefield starts again from the middle of the screen
+        if self.topology() == Topology::Endless {
+            self.clear_minefield(bg);
+            self.lay_out(bg);
+        }
+
+        // Draw the minefield
+        self.draw_minefield(bg);
+    }
+
+    pub fn show(&self, frame: &mut GraphicsFrame) {
+        self.cursor.show(frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use agb::{
+        Gba,
+        display::{
+            Priority,
+            tiled::{RegularBackgroundSize, TileFormat},
+        },
+        sound::mixer::Frequency,
+    };
+    use minesweeper_board::{Board, Buttons, is_solvable_by_logic};
+
+    use super::*;
+    use crate::{
+        CURSOR_MOVE,
+        shapes::{PUZZLES, SHAPES},
+    };
+
+    /// A plain minefield with mines only where they're put, played a frame at a time through
+    /// `update` on its own background
+    struct TestMinefield<'gba> {
+        minefield: Minefield,
+        bg: RegularBackground,
+        mixer: Mixer<'gba>,
+        input: Input,
+    }
+
+    impl<'gba> TestMinefield<'gba> {
+        fn new(gba: &'gba mut Gba, size: Vector2D<i32>, mines: &[Vec2]) -> Self {
+            let mut bg = RegularBackground::new(
+                Priority::P3,
+                RegularBackgroundSize::Background64x64,
+                TileFormat::FourBpp,
+            );
+            let mut minefield = Minefield::new(size, Theme::Classic, &CURSOR_MOVE);
+            *minefield.game.board_mut() = Board::with_mines(Vec2::new(size.x, size.y), mines);
+            minefield.draw_minefield(&mut bg);
+            Self {
+                minefield,
+                bg,
+                mixer: gba.mixer.mixer(Frequency::Hz32768),
+                input: Input::default(),
+            }
+        }
+
+        /// Run a frame with `buttons` held down
+        fn frame(&mut self, buttons: Buttons) -> MinefieldState {
+            self.input.update(buttons);
+            self.minefield
+                .update_with_input(&mut self.bg, &self.input, &mut self.mixer)
+        }
+
+        /// Press `buttons` and let go of them again
+        fn press(&mut self, buttons: Buttons) -> MinefieldState {
+            self.frame(buttons);
+            self.frame(Buttons::NONE)
+        }
+
+        /// What `block_pos` was last drawn as by `update`, if anything
+        fn drawn(&self, block_pos: Vec2) -> Option<Drawn> {
+            self.minefield
+                .drawn
+                .iter()
+                .rev()
+                .find(|&&(pos, _)| pos == block_pos)
+                .map(|&(_, drawn)| drawn)
+        }
+    }
+
+    #[test_case]
+    fn test_reveal_draws_what_is_underneath(gba: &mut Gba) {
+        let mines = [Vec2::new(0, 0), Vec2::new(1, 0)];
+        let mut test = TestMinefield::new(gba, vec2(4, 3), &mines);
+
+        // The block is pressed while the button is held and cleared once it's let go
+        test.press(Buttons::DOWN);
+        test.frame(Buttons::A);
+        assert_eq!(test.drawn(Vec2::new(0, 1)), Some(Drawn::Pressed));
+        assert_eq!(test.frame(Buttons::NONE), MinefieldState::Play);
+        assert_eq!(
+            test.drawn(Vec2::new(0, 1)),
+            Some(Drawn::Cleared(MinefieldItem::Number(2)))
+        );
+
+        test.press(Buttons::RIGHT);
+        test.press(Buttons::RIGHT);
+        test.press(Buttons::A);
+        assert_eq!(
+            test.drawn(Vec2::new(2, 1)),
+            Some(Drawn::Cleared(MinefieldItem::Number(1)))
+        );
+
+        // A mine ends the game
+        test.press(Buttons::UP);
+        test.press(Buttons::LEFT);
+        assert_eq!(test.minefield.game.cursor(), Vec2::new(1, 0));
+        assert_eq!(test.press(Buttons::A), MinefieldState::GameOver(false));
+        assert_eq!(
+            test.drawn(Vec2::new(1, 0)),
+            Some(Drawn::Cleared(MinefieldItem::Mine(1)))
+        );
+    }
+
+    #[test_case]
+    fn test_cycle_block_state(gba: &mut Gba) {
+        let mut test = TestMinefield::new(gba, vec2(2, 2), &[Vec2::new(1, 1)]);
+        let block_pos = Vec2::new(0, 0);
+        for block in [
+            MinefieldBlock::Flag(1),
+            MinefieldBlock::Question,
+            MinefieldBlock::Block,
+        ] {
+            test.press(Buttons::B);
+            assert_eq!(test.minefield.game.board().block(block_pos), block);
+            assert_eq!(test.drawn(block_pos), Some(Drawn::Block(block)));
+        }
+        assert_eq!(test.minefield.flag_count(), 0);
+
+        // Without question marks a flag goes straight back to a block
+        test.minefield.game.board_mut().set_question_marks(false);
+        test.press(Buttons::B);
+        assert_eq!(test.minefield.flag_count(), 1);
+        test.press(Buttons::B);
+        assert_eq!(
+            test.drawn(block_pos),
+            Some(Drawn::Block(MinefieldBlock::Block))
+        );
+
+        // Cleared blocks can't be cycled
+        test.press(Buttons::A);
+        test.press(Buttons::B);
+        assert_eq!(
+            test.drawn(block_pos),
+            Some(Drawn::Cleared(MinefieldItem::Number(1)))
+        );
+        assert_eq!(test.minefield.flag_count(), 0);
+    }
+
+    #[test_case]
+    fn test_edge_and_corner_bounds(gba: &mut Gba) {
+        // The bottom right corner is surrounded by mines
+        let mines = [Vec2::new(3, 2), Vec2::new(4, 2), Vec2::new(3, 3)];
+        let mut test = TestMinefield::new(gba, vec2(5, 4), &mines);
+
+        // The cursor stops at the edges without cursor wrap
+        let start_pos = test.minefield.cursor.pos;
+        test.press(Buttons::LEFT);
+        test.press(Buttons::UP);
+        assert_eq!(test.minefield.game.cursor(), Vec2::new(0, 0));
+        assert_eq!(test.minefield.cursor.pos, start_pos);
+        for _ in 0..8 {
+            test.press(Buttons::RIGHT);
+            test.press(Buttons::DOWN);
+        }
+        let corner = Vec2::new(4, 3);
+        assert_eq!(test.minefield.game.cursor(), corner);
+        assert_eq!(
+            test.minefield.cursor.pos,
+            test.minefield.block_to_pixel_pos(corner)
+        );
+
+        // A corner only counts the 3 blocks which are on the board
+        assert_eq!(test.press(Buttons::A), MinefieldState::Play);
+        assert_eq!(
+            test.drawn(corner),
+            Some(Drawn::Cleared(MinefieldItem::Number(3)))
+        );
+
+        // With cursor wrap it goes round to the opposite edge instead
+        test.minefield.game.set_cursor_wrap(true);
+        test.press(Buttons::RIGHT);
+        assert_eq!(test.minefield.game.cursor(), Vec2::new(0, 3));
+        test.press(Buttons::DOWN);
+        assert_eq!(test.minefield.game.cursor(), Vec2::new(0, 0));
+        assert_eq!(
+            test.minefield.cursor.pos,
+            test.minefield.block_to_pixel_pos(Vec2::new(0, 0))
+        );
+    }
+
+    #[test_case]
+    fn test_set_size_lays_out_again(gba: &mut Gba) {
+        let mut test = TestMinefield::new(gba, vec2(13, 8), &[]);
+        let minefield = &mut test.minefield;
+        assert_eq!(
+            minefield.pos,
+            vec2(16, layout::HUD_HEIGHT + 8).change_base()
+        );
+
+        // Too big to fit, so it starts against the top left below the HUD with the cursor on it
+        minefield.set_size(&mut test.bg, vec2(30, 16));
+        assert_eq!(minefield.pos, vec2(0, layout::HUD_HEIGHT).change_base());
+        assert_eq!(minefield.cursor.pos, minefield.pos);
+    }
+
+    #[test_case]
+    fn test_line_clues_make_room_beside_the_rows(gba: &mut Gba) {
+        let mut test = TestMinefield::new(gba, vec2(13, 8), &[]);
+        let (minefield, bg) = (&mut test.minefield, &mut test.bg);
+        let pos = minefield.pos;
+
+        // The minefield and the clues beside it are centred together
+        minefield.set_line_clues(bg, true);
+        assert_eq!(minefield.pos, pos + vec2(8, 0).change_base());
+
+        // Wrapping edges have their markers where the clues would go
+        minefield.set_topology(bg, Topology::Toroidal);
+        assert!(!minefield.shows_clues());
+        assert_eq!(minefield.pos, pos);
+
+        minefield.set_topology(bg, Topology::Bounded);
+        minefield.set_line_clues(bg, false);
+        assert_eq!(minefield.pos, pos);
+    }
+
+    #[test_case]
+    fn test_shapes_start_with_cursor_on_a_block(gba: &mut Gba) {
+        let mut test = TestMinefield::new(gba, vec2(13, 8), &[]);
+        let (minefield, bg) = (&mut test.minefield, &mut test.bg);
+        for shape in &SHAPES {
+            minefield.set_shape(bg, shape).reset(bg);
+            let cursor = minefield.game.cursor();
+            assert!(shape.is_playable(cursor), "{}", shape.name());
+            assert_eq!(minefield.cursor.pos, minefield.block_to_pixel_pos(cursor));
+        }
+
+        // Going back to a rectangle forgets the shape
+        minefield.set_size(bg, vec2(13, 8));
+        assert_eq!(minefield.shape(), None);
+    }
+
+    #[test_case]
+    fn test_puzzles_can_be_solved_without_guessing(_gba: &mut Gba) {
+        for puzzle in &PUZZLES {
+            let mut board = Board::new(Vec2::new(1, 1));
+            board.set_puzzle(puzzle);
+            assert!(!board.is_win_condition(), "{} starts won", puzzle.name());
+            assert!(
+                is_solvable_by_logic(&board),
+                "{} needs a guess",
+                puzzle.name()
+            );
+        }
+    }
+
+    #[test_case]
+    fn test_puzzles_replay_their_layout(gba: &mut Gba) {
+        let mut test = TestMinefield::new(gba, vec2(13, 8), &[]);
+        let (minefield, bg) = (&mut test.minefield, &mut test.bg);
+        minefield.set_grid(bg, Grid::Hex);
+        let puzzle = &PUZZLES[0];
+        minefield.set_puzzle(bg, puzzle);
+        assert_eq!(minefield.grid(), Grid::Square);
+
+        // Starting again puts the same mines back instead of random ones
+        minefield.game.board_mut().reveal();
+        minefield.reset(bg);
+        let board = minefield.game.board();
+        let size = board.size();
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let block_pos = Vec2::new(x, y);
+                assert_eq!(board.is_mine(block_pos), puzzle.has_mine(block_pos));
+                if puzzle.starts_cleared(block_pos) {
+                    assert_eq!(board.block(block_pos), MinefieldBlock::Clear);
+                }
+            }
+        }
+
+        // Picking a shape goes back to random mines
+        minefield.set_shape(bg, &SHAPES[0]);
+        assert!(minefield.puzzle().is_none());
+    }
+
+    #[test_case]
+    fn test_hex_rows_are_staggered(gba: &mut Gba) {
+        let mut test = TestMinefield::new(gba, vec2(13, 8), &[]);
+        let minefield = &mut test.minefield;
+        minefield.set_grid(&mut test.bg, Grid::Hex);
+
+        // Odd rows sit half a block to the right, and the minefield is re-centred to fit them
+        assert_eq!(minefield.block_pixel_offset(Vec2::new(2, 0)), vec2(32, 0));
+        assert_eq!(minefield.block_pixel_offset(Vec2::new(2, 1)), vec2(40, 16));
+        assert_eq!(
+            minefield.pos,
+            vec2(12, layout::HUD_HEIGHT + 8).change_base()
+        );
+    }
+
+    #[test_case]
+    fn test_endless_minefield_scrolls_with_cursor(gba: &mut Gba) {
+        let mut test = TestMinefield::new(gba, vec2(13, 8), &[]);
+        test.minefield
+            .set_topology(&mut test.bg, Topology::Endless)
+            .reset(&mut test.bg);
+        assert_eq!(test.minefield.game.cursor(), Vec2::new(0, 0));
+        assert_eq!(test.minefield.cleared_blocks(), 0);
+        let start_pos = test.minefield.pos;
+
+        // Walking left carries on past where a bounded minefield would stop, scrolling as it goes
+        for _ in 0..20 {
+            test.press(Buttons::LEFT);
+        }
+        let minefield = &mut test.minefield;
+        assert_eq!(minefield.game.cursor(), Vec2::new(-20, 0));
+        assert!(minefield.pos.x > start_pos.x);
+        let view = minefield.view().unwrap();
+        assert!(in_view(view, minefield.game.cursor()));
+
+        // Going back to solid edges lays out a plain minefield again
+        minefield.set_topology(&mut test.bg, Topology::Bounded);
+        assert!(minefield.view().is_none());
+        assert_eq!(
+            minefield.pos,
+            vec2(16, layout::HUD_HEIGHT + 8).change_base()
+        );
+    }
+
+    #[test_case]
+    fn test_floors_are_shown_one_at_a_time(gba: &mut Gba) {
+        let mut test = TestMinefield::new(gba, vec2(13, 8), &[]);
+        test.minefield
+            .set_floors(&mut test.bg, 3)
+            .reset(&mut test.bg);
+        assert_eq!(
+            test.minefield.view(),
+            Some((Vec2::new(0, 0), Vec2::new(13, 8)))
+        );
+        let cursor_pos = test.minefield.cursor.pos;
+
+        // SELECT+R goes down a floor, which is shown in the same place with the cursor where it was
+        test.press(Buttons::SELECT | Buttons::R);
+        assert_eq!(test.minefield.game.cursor(), Vec2::new(0, 8));
+        assert_eq!(
+            test.minefield.view(),
+            Some((Vec2::new(0, 8), Vec2::new(13, 8)))
+        );
+        assert_eq!(test.minefield.cursor.pos, cursor_pos);
+        assert!(test.minefield.floor_slide > 0);
+
+        // The HUD can see the top floor above, and nothing below the bottom one
+        let floor_hint = test.minefield.floor_hint().unwrap();
+        assert_eq!(floor_hint.floor, 1);
+        assert!(floor_hint.up.is_some());
+        test.press(Buttons::SELECT | Buttons::R);
+        assert!(test.minefield.floor_hint().unwrap().down.is_none());
+
+        // The new floor settles into place after a few frames
+        for _ in 0..FLOOR_SLIDE_DISTANCE / FLOOR_SLIDE_SPEED {
+            test.frame(Buttons::NONE);
+        }
+        assert_eq!(test.minefield.floor_slide, 0);
+    }
+
+    #[test_case]
+    fn test_cascade_in_update(gba: &mut Gba) {
+        let mut test = TestMinefield::new(gba, vec2(4, 4), &[Vec2::new(3, 3)]);
+
+        // Press and release reveal on the top left block
+        let mut state = test.press(Buttons::A);
+        assert_eq!(state, MinefieldState::Play);
+        assert!(test.minefield.game.board().is_cascading());
+        assert_eq!(
+            test.drawn(Vec2::new(0, 0)),
+            Some(Drawn::Cleared(MinefieldItem::Blank))
+        );
+        assert_eq!(test.drawn(Vec2::new(3, 0)), None);
+
+        // The cascade clears the rest of the board without the player doing anything
+        let mut frames = 0;
+        while state == MinefieldState::Play {
+            state = test.frame(Buttons::NONE);
+            frames += 1;
+            assert!(frames < 100, "cascade never finished");
+        }
+        assert_eq!(state, MinefieldState::GameOver(true));
+        assert_eq!(
+            test.drawn(Vec2::new(3, 0)),
+            Some(Drawn::Cleared(MinefieldItem::Blank))
+        );
+        assert_eq!(
+            test.drawn(Vec2::new(2, 2)),
+            Some(Drawn::Cleared(MinefieldItem::Number(1)))
+        );
+        assert_eq!(test.drawn(Vec2::new(3, 3)), None);
+        assert_eq!(
+            test.minefield.game.board().block(Vec2::new(3, 3)),
+            MinefieldBlock::Block
+        );
+    }
+
+    #[test_case]
+    fn test_casual_chord_redraws_clues_away_from_the_cursor(gba: &mut Gba) {
+        let mut test = TestMinefield::new(gba, vec2(3, 3), &[Vec2::new(0, 0)]);
+        let (minefield, bg) = (&mut test.minefield, &mut test.bg);
+        minefield
+            .set_line_clues(bg, true)
+            .set_lives_per_game(Some(2));
+
+        // Flag the wrong block around the 1 in the middle, then chord it into the mine
+        let board = minefield.game.board_mut();
+        board.toggle_flag(Vec2::new(2, 2));
+        board.reveal_block(Vec2::new(1, 1));
+        minefield.game.controls_mut().set_reveal_on_press(true);
+        let mut input = Input::default();
+        for buttons in [Buttons::DOWN, Buttons::NONE, Buttons::RIGHT, Buttons::NONE] {
+            input.update(buttons);
+            minefield.game.update(&input, &mut ());
+        }
+        assert_eq!(minefield.game.cursor(), Vec2::new(1, 1));
+        input.update(Buttons::L);
+        let mut renderer = minefield.renderer(bg);
+        assert_eq!(
+            minefield.game.update(&input, &mut renderer),
+            MinefieldState::Play
+        );
+
+        // The mine is flagged in the corner, so the clues for its row and column need drawing
+        assert_eq!(
+            minefield.game.board().block(Vec2::new(0, 0)),
+            MinefieldBlock::Flag(1)
+        );
+        assert!(renderer.changed_rows.contains(&0));
+        assert!(renderer.changed_columns.contains(&0));
+    }
+}
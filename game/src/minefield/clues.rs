@@ -0,0 +1,84 @@
+use agb::{
+    display::{
+        tile_data::TileData,
+        tiled::{RegularBackground, TileSetting},
+    },
+    fixnum::Vector2D,
+};
+use minesweeper_board::LineClue;
+
+// Glyphs in the clue tiles, each a pair of tiles side by side. The first 10 are the tens and units
+// of the numbers 0 to 9, followed by a minus sign in the tens and then each digit on its own.
+const MINUS_GLYPH: usize = 10;
+const SINGLE_GLYPHS: usize = 11;
+
+// Tile row of the first of the glyphs for the clues beside the rows, which are twice as tall so
+// that they sit in the middle of a block
+const TALL_GLYPHS_ROW: usize = 21;
+
+/// Glyphs for the left and right of `n`: its tens and units, or a digit on its own across both
+fn glyphs(n: i32) -> (usize, usize) {
+    match n {
+        0..=9 => (SINGLE_GLYPHS + n as usize, SINGLE_GLYPHS + n as usize),
+        10.. => {
+            let n = n.min(99) as usize;
+            (n / 10, n % 10)
+        }
+        _ => (MINUS_GLYPH, n.unsigned_abs().min(9) as usize),
+    }
+}
+
+/// Draw how many mines are left to flag for `clue`, 2 tiles across from `tile_pos` and 2 down if
+/// `tall`, or blank it for `None`
+///
+/// A line with no blocks in it is left blank too.
+pub fn draw_clue(
+    bg: &mut RegularBackground,
+    tile_pos: Vector2D<i32>,
+    clue: Option<LineClue>,
+    tile_data: &TileData,
+    tall: bool,
+) {
+    let glyphs = clue
+        .filter(|clue| clue.blocks > 0)
+        .map(|clue| glyphs(clue.remaining()));
+    let rows = if tall { 2 } else { 1 };
+    for y in 0..rows {
+        for x in 0..2 {
+            let tile_setting = match glyphs {
+                Some((left, right)) => {
+                    let glyph = if x == 0 { left } else { right };
+                    let row = if tall {
+                        TALL_GLYPHS_ROW + glyph * 2 + y
+                    } else {
+                        glyph
+                    };
+                    tile_data.tile_settings[row * 2 + x]
+                }
+                None => TileSetting::BLANK,
+            };
+            bg.set_tile(
+                (tile_pos.x + x as i32, tile_pos.y + y as i32),
+                &tile_data.tiles,
+                tile_setting,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use agb::Gba;
+
+    use super::*;
+
+    #[test_case]
+    fn test_clue_glyphs(_gba: &mut Gba) {
+        assert_eq!(glyphs(0), (SINGLE_GLYPHS, SINGLE_GLYPHS));
+        assert_eq!(glyphs(7), (SINGLE_GLYPHS + 7, SINGLE_GLYPHS + 7));
+        assert_eq!(glyphs(42), (4, 2));
+
+        // Too many flags show as below 0
+        assert_eq!(glyphs(-3), (MINUS_GLYPH, 3));
+    }
+}
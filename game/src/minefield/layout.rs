@@ -0,0 +1,142 @@
+use agb::{
+    display::{HEIGHT, WIDTH},
+    fixnum::{Vector2D, vec2},
+};
+
+// Size of a block in pixels
+pub const BLOCK_SIZE: i32 = 16;
+
+// Rows of pixels at the top of the screen kept clear of the minefield for the HUD
+pub const HUD_HEIGHT: i32 = 16;
+
+// Blocks to keep between the cursor and the edge of the screen while scrolling
+const SCROLL_MARGIN: i32 = 1;
+
+/// The part of the screen the minefield is drawn in, below the HUD
+fn view_pos() -> Vector2D<i32> {
+    vec2(0, HUD_HEIGHT)
+}
+
+fn view_size() -> Vector2D<i32> {
+    vec2(WIDTH, HEIGHT - HUD_HEIGHT)
+}
+
+/// Pixel position of a board of pixel `size` (w x h), currently at pixel position `pos`
+///
+/// Boards which fit are centred below the HUD. Bigger ones scroll only as far as needed to keep
+/// the block at `cursor`, in pixels from the top left of the board, in view without showing past
+/// the edges of the board.
+pub fn board_pos(size: Vector2D<i32>, cursor: Vector2D<i32>, pos: Vector2D<i32>) -> Vector2D<i32> {
+    let view_pos = view_pos();
+    let view_size = view_size();
+    vec2(
+        axis_pos(view_pos.x, view_size.x, size.x, cursor.x, pos.x),
+        axis_pos(view_pos.y, view_size.y, size.y, cursor.y, pos.y),
+    )
+}
+
+/// Pixel position of an endless board, which has no edges to stop scrolling at
+///
+/// Without a `pos` to scroll from, the block at `cursor` starts in the middle of the view, lined
+/// up with the edges of the view.
+pub fn endless_pos(cursor: Vector2D<i32>, pos: Option<Vector2D<i32>>) -> Vector2D<i32> {
+    let view_pos = view_pos();
+    let view_size = view_size();
+    let Some(pos) = pos else {
+        let middle = (view_size / BLOCK_SIZE - vec2(1, 1)) / 2 * BLOCK_SIZE;
+        return view_pos + middle - cursor;
+    };
+    vec2(
+        scroll_to_cursor(view_pos.x, view_size.x, cursor.x, pos.x),
+        scroll_to_cursor(view_pos.y, view_size.y, cursor.y, pos.y),
+    )
+}
+
+/// The blocks on screen with a board at pixel position `pos`, as the first block and how many
+/// across and down
+///
+/// This covers the whole screen, including what's behind the HUD.
+pub fn visible_blocks(pos: Vector2D<i32>) -> (Vector2D<i32>, Vector2D<i32>) {
+    let first = vec2(
+        (-pos.x).div_euclid(BLOCK_SIZE),
+        (-pos.y).div_euclid(BLOCK_SIZE),
+    );
+    let last = vec2(
+        (WIDTH - 1 - pos.x).div_euclid(BLOCK_SIZE),
+        (HEIGHT - 1 - pos.y).div_euclid(BLOCK_SIZE),
+    );
+    (first, last - first + vec2(1, 1))
+}
+
+fn axis_pos(view_start: i32, view_len: i32, board_len: i32, cursor: i32, pos: i32) -> i32 {
+    if board_len <= view_len {
+        return view_start + (view_len - board_len) / 2;
+    }
+
+    // Never scroll past the edges of the board
+    let view_end = view_start + view_len;
+    scroll_to_cursor(view_start, view_len, cursor, pos).clamp(view_end - board_len, view_start)
+}
+
+/// Scroll until the blocks around the cursor are on screen
+fn scroll_to_cursor(view_start: i32, view_len: i32, cursor: i32, pos: i32) -> i32 {
+    let view_end = view_start + view_len;
+    let first_shown = cursor - SCROLL_MARGIN * BLOCK_SIZE;
+    let last_shown = cursor + (1 + SCROLL_MARGIN) * BLOCK_SIZE;
+    pos.max(view_start - first_shown).min(view_end - last_shown)
+}
+
+#[cfg(test)]
+mod tests {
+    use agb::Gba;
+
+    use super::*;
+
+    #[test_case]
+    fn test_small_board_is_centred(_gba: &mut Gba) {
+        let size = vec2(13, 8) * BLOCK_SIZE;
+
+        let pos = board_pos(size, vec2(0, 0), vec2(0, 0));
+        assert_eq!(pos, vec2(16, HUD_HEIGHT + 8));
+
+        // The cursor doesn't move a board which fits
+        assert_eq!(board_pos(size, vec2(12, 7) * BLOCK_SIZE, pos), pos);
+    }
+
+    #[test_case]
+    fn test_big_board_scrolls_with_cursor(_gba: &mut Gba) {
+        let size = vec2(30, 16) * BLOCK_SIZE;
+
+        // Starts against the top left of the view
+        let pos = board_pos(size, vec2(0, 0), vec2(0, 0));
+        assert_eq!(pos, vec2(0, HUD_HEIGHT));
+
+        // Moving within the view doesn't scroll
+        assert_eq!(board_pos(size, vec2(5, 5) * BLOCK_SIZE, pos), pos);
+
+        // Moving towards the right edge of the view scrolls to keep a block beside the cursor
+        let pos = board_pos(size, vec2(14, 0) * BLOCK_SIZE, pos);
+        assert_eq!(pos.x, WIDTH - 16 * BLOCK_SIZE);
+
+        // Wrapping to the far corner stops at the edges of the board
+        let pos = board_pos(size, vec2(29, 15) * BLOCK_SIZE, pos);
+        assert_eq!(pos, vec2(WIDTH, HEIGHT) - size);
+    }
+
+    #[test_case]
+    fn test_endless_board_follows_cursor(_gba: &mut Gba) {
+        // Starts with the cursor in the middle of the view, lined up with the blocks below the HUD
+        let pos = endless_pos(vec2(0, 0), None);
+        assert_eq!(pos, vec2(7 * BLOCK_SIZE, HUD_HEIGHT + 3 * BLOCK_SIZE));
+        let (first, size) = visible_blocks(pos);
+        assert_eq!(first, vec2(-7, -4));
+        assert_eq!(size, vec2(15, 10));
+
+        // There's no edge to stop at, however far the cursor goes
+        let cursor = vec2(-40, -30) * BLOCK_SIZE;
+        let pos = endless_pos(cursor, Some(pos));
+        assert_eq!(pos.x, BLOCK_SIZE - cursor.x);
+        assert_eq!(pos.y, HUD_HEIGHT + BLOCK_SIZE - cursor.y);
+        assert_eq!(endless_pos(cursor, Some(pos)), pos);
+    }
+}
@@ -3,7 +3,7 @@ use agb::{
         GraphicsFrame,
         object::{Object, Sprite},
     },
-    fixnum::{Rect, Vector2D, num, vec2},
+    fixnum::{Vector2D, num},
     sound::mixer::{Mixer, SoundChannel, SoundData},
 };
 
@@ -28,6 +28,11 @@ impl PlayerCursor {
         }
     }
 
+    pub fn set_sprite(&mut self, sprite_cursor: &'static Sprite) -> &mut Self {
+        self.sprite_cursor = sprite_cursor;
+        self
+    }
+
     pub fn set_pos(&mut self, pos: Vector2D<Fixed>) -> &mut Self {
         self.pos = pos;
         self
@@ -42,14 +47,20 @@ impl PlayerCursor {
         self
     }
 
+    pub fn wrap_to(&mut self, pos: Vector2D<Fixed>, mixer: &mut Mixer) -> &mut Self {
+        self.pos = pos;
+
+        // Pitch the move sound up so that wrapping sounds distinct from a normal move
+        let mut wrap_sound = SoundChannel::new(*self.sound_cursor_move);
+        wrap_sound.playback(num!(1.5));
+        mixer.play_sound(wrap_sound);
+        self
+    }
+
     pub fn show(&self, frame: &mut GraphicsFrame) {
         let sprite_pos = self.pos.round();
         Object::new(self.sprite_cursor)
             .set_pos(sprite_pos)
             .show(frame);
     }
-
-    pub fn collision_rect(&self) -> Rect<Fixed> {
-        Rect::new(self.pos, vec2(num!(16), num!(16)))
-    }
 }
@@ -0,0 +1,161 @@
+use agb::{
+    display::{Palette16, Rgb15, tile_data::TileData, tiled::VRAM_MANAGER},
+    include_background_gfx,
+};
+
+use crate::theme::{Theme, colour, colours};
+
+// Numbers with an extra mark on the 7 and 8, so they can be told apart by shape as well as colour
+include_background_gfx!(
+    mod cued_numbers,
+    "16171a",
+    NUMBERS => deduplicate "gfx/numbers_cued.aseprite",
+);
+
+// The highest number there are tiles for
+pub const MAX_NUMBER: u32 = 72;
+
+// Numbers from 9 to 72, which only come up in neighbourhoods with more than 8 blocks or blocks
+// with several mines
+include_background_gfx!(
+    mod extended_numbers,
+    "16171a",
+    NUMBERS => deduplicate "gfx/numbers_extended.aseprite",
+);
+
+// The one colour every extended number is drawn in
+const EXTENDED_NUMBER_COLOUR: Rgb15 = colour(0xfafdff);
+
+// Background palette for the number 1, the rest follow it. Each number gets a whole palette of its
+// own colour, so that changing the colours of the numbers doesn't touch anything else.
+const NUMBER_PALETTES_START: u8 = 8;
+
+/// Colours for the numbers 1 to 8
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum NumberPalette {
+    #[default]
+    Theme, // whatever the theme uses
+    Deuteranopia,
+    Protanopia,
+    HighContrast,
+}
+
+// Colours for each palette on dark and light backdrops, from 1 to 8
+const DEUTERANOPIA_DARK: [Rgb15; 8] = colours([
+    0xffffff, 0x56b4e9, 0xf0e442, 0xcc79a7, 0xe69f00, 0x009e73, 0xd55e00, 0x6e8cff,
+]);
+const DEUTERANOPIA_LIGHT: [Rgb15; 8] = colours([
+    0x0050a0, 0xa04800, 0x00604a, 0x8c3c78, 0x000000, 0x0090d0, 0x6a4a00, 0x505050,
+]);
+const PROTANOPIA_DARK: [Rgb15; 8] = colours([
+    0xffffff, 0x56b4e9, 0xf0e442, 0x8c8cff, 0xe69f00, 0xa0a0a0, 0x00c8c8, 0xcc79a7,
+]);
+const PROTANOPIA_LIGHT: [Rgb15; 8] = colours([
+    0x0050a0, 0x7a5a00, 0x004a6a, 0x5a3ca0, 0x000000, 0x505050, 0x007878, 0x8c3c78,
+]);
+const HIGH_CONTRAST_DARK: [Rgb15; 8] = colours([
+    0xffffff, 0x00ffff, 0xffff00, 0xff00ff, 0x00ff00, 0xff8000, 0xff4040, 0x8080ff,
+]);
+const HIGH_CONTRAST_LIGHT: [Rgb15; 8] = colours([
+    0x0000c0, 0x006000, 0xc00000, 0x000060, 0x600000, 0x006060, 0x000000, 0x600060,
+]);
+
+impl NumberPalette {
+    pub const ALL: [NumberPalette; 4] = [
+        NumberPalette::Theme,
+        NumberPalette::Deuteranopia,
+        NumberPalette::Protanopia,
+        NumberPalette::HighContrast,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            NumberPalette::Theme => "THEME",
+            NumberPalette::Deuteranopia => "DEUTAN",
+            NumberPalette::Protanopia => "PROTAN",
+            NumberPalette::HighContrast => "HI-CON",
+        }
+    }
+
+    fn colours(self, theme: Theme) -> [Rgb15; 8] {
+        let gfx = theme.gfx();
+        match (self, gfx.light_backdrop) {
+            (NumberPalette::Theme, _) => gfx.number_colours,
+            (NumberPalette::Deuteranopia, false) => DEUTERANOPIA_DARK,
+            (NumberPalette::Deuteranopia, true) => DEUTERANOPIA_LIGHT,
+            (NumberPalette::Protanopia, false) => PROTANOPIA_DARK,
+            (NumberPalette::Protanopia, true) => PROTANOPIA_LIGHT,
+            (NumberPalette::HighContrast, false) => HIGH_CONTRAST_DARK,
+            (NumberPalette::HighContrast, true) => HIGH_CONTRAST_LIGHT,
+        }
+    }
+}
+
+/// The first of `palettes` with `colour` in it, other than as the transparent colour at index 0
+fn palette_with(palettes: &[Palette16], colour: Rgb15) -> Option<&Palette16> {
+    palettes
+        .iter()
+        .find(|palette| (1..16).any(|i| palette.colour(i) == colour))
+}
+
+/// Load the palettes for `theme`, with the numbers coloured by `number_palette` and drawn with the
+/// cued digits if `number_cues` is set
+///
+/// Each number's palette is the one its digit was drawn with, with only the entries holding the
+/// digit's colour changed, so the transparent colour and everything else stays as drawn.
+pub fn load_palettes(theme: Theme, number_palette: NumberPalette, number_cues: bool) {
+    let gfx = theme.gfx();
+    debug_assert!(gfx.palettes.len() <= NUMBER_PALETTES_START as usize);
+    VRAM_MANAGER.set_background_palettes(gfx.palettes);
+
+    // The cued digits are only drawn the once, in the classic theme's colours
+    let (digit_palettes, drawn_colours) = if number_cues {
+        (cued_numbers::PALETTES, Theme::Classic.gfx().number_colours)
+    } else {
+        (gfx.palettes, gfx.number_colours)
+    };
+    let extended_palette = palette_with(extended_numbers::PALETTES, EXTENDED_NUMBER_COLOUR);
+    for (i, colour) in number_palette.colours(theme).into_iter().enumerate() {
+        let digit_palette = palette_with(digit_palettes, drawn_colours[i]);
+        debug_assert!(
+            digit_palette.is_some(),
+            "the number {} has no palette",
+            i + 1
+        );
+        let mut palette = digit_palette.unwrap_or(&digit_palettes[0]).clone();
+        for index in 1..16 {
+            if palette.colour(index) == drawn_colours[i] {
+                palette.update_colour(index, colour);
+            }
+        }
+
+        // The extended numbers share the palette, so colour wherever they have their one colour
+        if let Some(extended_palette) = extended_palette {
+            for index in 1..16 {
+                if extended_palette.colour(index) == EXTENDED_NUMBER_COLOUR {
+                    palette.update_colour(index, colour);
+                }
+            }
+        }
+        VRAM_MANAGER.set_background_palette(NUMBER_PALETTES_START + i as u8, &palette);
+    }
+}
+
+/// The background palette to draw the number `n` with
+///
+/// There are only palettes for the numbers 1 to 8, so the numbers above 8 take the colour of the
+/// number with the same remainder, 9 that of 1 and so on. The colours can't tell them apart, but
+/// their digits are different enough to read on their own.
+pub fn number_palette_id(n: u32) -> u8 {
+    NUMBER_PALETTES_START + ((n - 1) % 8) as u8
+}
+
+/// Tiles for the numbers with the marks on the 7 and 8
+pub fn cued_number_tiles() -> &'static TileData {
+    &cued_numbers::NUMBERS
+}
+
+/// Tiles for the numbers from 9 to 72
+pub fn extended_number_tiles() -> &'static TileData {
+    &extended_numbers::NUMBERS
+}
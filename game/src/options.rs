@@ -0,0 +1,196 @@
+use minesweeper_board::{
+    Buttons, CascadeSpeed, Grid, MAX_FLOORS, MAX_MINES_PER_BLOCK, Neighbourhood, Shape, Topology,
+};
+
+use crate::{
+    controls::Bindings,
+    number_palette::{MAX_NUMBER, NumberPalette},
+    shapes::SHAPES,
+    theme::Theme,
+};
+
+// Number of bytes used to store the options in save data
+pub const OPTIONS_SIZE: usize = 14;
+
+// Lives each casual game starts with
+const CASUAL_LIVES: u32 = 3;
+
+/// Player configurable settings which change how the game behaves
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct Options {
+    pub cursor_wrap: bool, // moving past an edge of the minefield wraps to the opposite edge
+    pub question_marks: bool, // cycling a block goes through the question mark after the flag
+    pub reveal_on_press: bool, // reveal as soon as the button is pressed instead of released
+    pub cascade_speed: CascadeSpeed,
+    pub puzzles: bool, // play the designed puzzles instead of random minefields
+    pub shape: Option<&'static Shape>, // a designed board shape, or None for a plain rectangle
+    pub grid: Grid,
+    pub topology: Topology,
+    pub neighbourhood: Neighbourhood,
+    pub multi_mines: bool, // blocks can hold several mines, and take as many flags
+    pub floors: i32,       // floors stacked up, switched between with L and R and the jump button
+    pub casual: bool,      // revealing a mine costs one of a few lives instead of the game
+    pub line_clues: bool,  // show the mines in each row and column beside them
+    pub theme: Theme,
+    pub number_palette: NumberPalette,
+    pub number_cues: bool, // marks on the 7 and 8 so that they don't rely on colour
+    pub bindings: Bindings,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            cursor_wrap: false,
+            question_marks: true,
+            reveal_on_press: false,
+            cascade_speed: CascadeSpeed::default(),
+            puzzles: false,
+            shape: None,
+            grid: Grid::default(),
+            topology: Topology::default(),
+            neighbourhood: Neighbourhood::default(),
+            multi_mines: false,
+            floors: 1,
+            casual: false,
+            line_clues: false,
+            theme: Theme::default(),
+            number_palette: NumberPalette::default(),
+            number_cues: false,
+            bindings: Bindings::default(),
+        }
+    }
+}
+
+impl Options {
+    /// The most mines a new game can put in one block
+    pub fn max_mines_per_block(self) -> u32 {
+        if self.multi_mines {
+            MAX_MINES_PER_BLOCK
+        } else {
+            1
+        }
+    }
+
+    /// Lives a new game starts with, or `None` for the standard game
+    pub fn lives_per_game(self) -> Option<u32> {
+        self.casual.then_some(CASUAL_LIVES)
+    }
+
+    /// How many floors a new game has, which is only ever 1 for an endless minefield
+    pub fn floors(self) -> i32 {
+        if self.topology == Topology::Endless {
+            1
+        } else {
+            self.floors
+        }
+    }
+
+    /// Whether every number a new game could show has tiles to draw it with
+    ///
+    /// Mines are counted on the floors above and below as well, so not every neighbourhood can have
+    /// several floors and several mines per block.
+    pub fn fits_numbers(self) -> bool {
+        let max_neighbours = self.neighbourhood.max_neighbours(self.grid, self.floors());
+        max_neighbours as u32 * self.max_mines_per_block() <= MAX_NUMBER
+    }
+
+    pub fn to_bytes(self) -> [u8; OPTIONS_SIZE] {
+        let mut bytes = [0u8; OPTIONS_SIZE];
+        let cascade_speed = match self.cascade_speed {
+            CascadeSpeed::Classic => 0,
+            CascadeSpeed::Fast => 1,
+            CascadeSpeed::Instant => 2,
+        };
+        bytes[0] = self.cursor_wrap as u8
+            | (self.question_marks as u8) << 1
+            | (self.reveal_on_press as u8) << 2
+            | cascade_speed << 3
+            | (self.theme as u8) << 5
+            | (self.puzzles as u8) << 7;
+
+        // Buttons fit in 16 bits, so store each binding as 2 bytes
+        let bindings = [
+            self.bindings.reveal,
+            self.bindings.cycle,
+            self.bindings.flag,
+            self.bindings.chord,
+            self.bindings.jump,
+        ];
+        for (i, button) in bindings.iter().enumerate() {
+            bytes[1 + i * 2..3 + i * 2].copy_from_slice(&button.bits().to_le_bytes());
+        }
+
+        let grid = match self.grid {
+            Grid::Square => 0,
+            Grid::Hex => 1,
+        };
+        // This byte is full, so endless boards are marked in the last one instead
+        let topology = match self.topology {
+            Topology::Bounded | Topology::Endless => 0,
+            Topology::Toroidal => 1,
+        };
+        bytes[11] = self.number_palette as u8
+            | (self.number_cues as u8) << 2
+            | grid << 3
+            | topology << 4
+            | (self.neighbourhood as u8) << 5
+            | (self.multi_mines as u8) << 7;
+
+        // 0 for a rectangle, otherwise 1 more than the index into the shapes
+        bytes[12] = self
+            .shape
+            .and_then(|shape| SHAPES.iter().position(|s| s == shape))
+            .map_or(0, |index| index as u8 + 1);
+        // Stored as the floors above the first
+        bytes[13] = (self.topology == Topology::Endless) as u8
+            | (self.line_clues as u8) << 1
+            | ((self.floors - 1) as u8) << 2
+            | (self.casual as u8) << 4;
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; OPTIONS_SIZE]) -> Self {
+        let button = |i: usize| {
+            let bits = u16::from_le_bytes([bytes[1 + i * 2], bytes[2 + i * 2]]);
+            Buttons::from_bits_truncate(bits)
+        };
+        Self {
+            cursor_wrap: bytes[0] & 1 != 0,
+            question_marks: bytes[0] & 2 != 0,
+            reveal_on_press: bytes[0] & 4 != 0,
+            cascade_speed: match (bytes[0] >> 3) & 3 {
+                1 => CascadeSpeed::Fast,
+                2 => CascadeSpeed::Instant,
+                _ => CascadeSpeed::Classic,
+            },
+            puzzles: bytes[0] & 0x80 != 0,
+            shape: (bytes[12] as usize)
+                .checked_sub(1)
+                .and_then(|index| SHAPES.get(index)),
+            grid: match (bytes[11] >> 3) & 1 {
+                1 => Grid::Hex,
+                _ => Grid::Square,
+            },
+            topology: match ((bytes[11] >> 4) & 1, bytes[13] & 1) {
+                (_, 1) => Topology::Endless,
+                (1, _) => Topology::Toroidal,
+                _ => Topology::Bounded,
+            },
+            neighbourhood: Neighbourhood::ALL[(bytes[11] >> 5) as usize & 3],
+            multi_mines: bytes[11] & 0x80 != 0,
+            floors: (1 + ((bytes[13] >> 2) & 3) as i32).min(MAX_FLOORS),
+            casual: bytes[13] & 0x10 != 0,
+            line_clues: bytes[13] & 2 != 0,
+            theme: Theme::ALL[(bytes[0] >> 5) as usize & 3],
+            number_palette: NumberPalette::ALL[bytes[11] as usize & 3],
+            number_cues: bytes[11] & 4 != 0,
+            bindings: Bindings {
+                reveal: button(0),
+                cycle: button(1),
+                flag: button(2),
+                chord: button(3),
+                jump: button(4),
+            },
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use alloc::{format, string::String};
+
+use agb::{
+    display::{
+        GraphicsFrame, Priority,
+        tile_data::TileData,
+        tiled::{RegularBackground, RegularBackgroundSize, TileFormat},
+    },
+    fixnum::vec2,
+    input::{Button, ButtonController, Tri},
+};
+use minesweeper_board::{Buttons, CascadeSpeed, Grid, Neighbourhood, Shape, Topology};
+
+use crate::{
+    controls::Bindings,
+    number_palette::{MAX_NUMBER, NumberPalette},
+    options::Options,
+    shapes::SHAPES,
+    text::draw_text,
+    theme::Theme,
+};
+
+// const expressions
+const MENU_ITEM_COUNT: usize = 21;
+const MENU_ITEMS_TILE_Y: i32 = 3;
+// Rows between the title and the footer, the menu scrolls to show the rest
+const VISIBLE_ITEM_COUNT: usize = 16;
+const LABEL_WIDTH: usize = 15;
+const VALUE_WIDTH: usize = 7;
+// The footer runs from the left margin to the edge of the screen
+const FOOTER_WIDTH: usize = 27;
+const CASCADE_SPEEDS: [CascadeSpeed; 3] = [
+    CascadeSpeed::Classic,
+    CascadeSpeed::Fast,
+    CascadeSpeed::Instant,
+];
+const GRIDS: [Grid; 2] = [Grid::Square, Grid::Hex];
+const TOPOLOGIES: [Topology; 3] = [Topology::Bounded, Topology::Toroidal, Topology::Endless];
+const FLOORS: [i32; 3] = [1, 2, 3];
+// START opens this menu and the directions move the cursor, so neither can be bound
+const BINDABLE_BUTTONS: [Buttons; 5] = [
+    Buttons::A,
+    Buttons::B,
+    Buttons::L,
+    Buttons::R,
+    Buttons::SELECT,
+];
+const BINDING_COUNT: usize = 5;
+
+/// A setting which can be changed from the menu
+enum MenuValue<'a> {
+    Toggle(&'a mut bool),
+    CascadeSpeed(&'a mut CascadeSpeed),
+    Shape(&'a mut Option<&'static Shape>),
+    Grid(&'a mut Grid),
+    Topology(&'a mut Topology),
+    Neighbourhood(&'a mut Neighbourhood),
+    Floors(&'a mut i32),
+    Binding(&'a mut Bindings, usize),
+    Theme(&'a mut Theme),
+    NumberPalette(&'a mut NumberPalette),
+}
+
+impl MenuValue<'_> {
+    /// Text for the current value, at most `VALUE_WIDTH` characters
+    fn text(&self) -> &'static str {
+        match self {
+            MenuValue::Toggle(true) => "ON",
+            MenuValue::Toggle(false) => "OFF",
+            MenuValue::CascadeSpeed(CascadeSpeed::Classic) => "CLASSIC",
+            MenuValue::CascadeSpeed(CascadeSpeed::Fast) => "FAST",
+            MenuValue::CascadeSpeed(CascadeSpeed::Instant) => "INSTANT",
+            MenuValue::Shape(None) => "PLAIN",
+            MenuValue::Shape(Some(shape)) => shape.name(),
+            MenuValue::Grid(Grid::Square) => "SQUARE",
+            MenuValue::Grid(Grid::Hex) => "HEX",
+            MenuValue::Topology(Topology::Bounded) => "SOLID",
+            MenuValue::Topology(Topology::Toroidal) => "WRAP",
+            MenuValue::Topology(Topology::Endless) => "ENDLESS",
+            MenuValue::Neighbourhood(Neighbourhood::Standard) => "NORMAL",
+            MenuValue::Neighbourhood(Neighbourhood::Cross) => "CROSS",
+            MenuValue::Neighbourhood(Neighbourhood::Knight) => "KNIGHT",
+            MenuValue::Neighbourhood(Neighbourhood::Radius2) => "5X5",
+            MenuValue::Floors(1) => "1",
+            MenuValue::Floors(2) => "2",
+            MenuValue::Floors(_) => "3",
+            MenuValue::Binding(bindings, action) => match *binding_mut(bindings, *action) {
+                Buttons::A => "A",
+                Buttons::B => "B",
+                Buttons::L => "L",
+                Buttons::R => "R",
+                Buttons::SELECT => "SELECT",
+                _ => "-",
+            },
+            MenuValue::Theme(theme) => theme.name(),
+            MenuValue::NumberPalette(number_palette) => number_palette.name(),
+        }
+    }
+
+    /// Step the value forwards or backwards by `step`
+    fn change(&mut self, step: i32) {
+        match self {
+            MenuValue::Toggle(enabled) => **enabled = !**enabled,
+            MenuValue::CascadeSpeed(cascade_speed) => {
+                step_through(&CASCADE_SPEEDS, &mut **cascade_speed, step)
+            }
+            MenuValue::Shape(shape) => {
+                // The plain rectangle comes before the designed shapes
+                let index = shape.map_or(0, |shape| {
+                    1 + SHAPES.iter().position(|s| s == shape).unwrap_or(0)
+                }) as i32;
+                let next_index = (index + step).rem_euclid(1 + SHAPES.len() as i32) as usize;
+                **shape = next_index.checked_sub(1).map(|index| &SHAPES[index]);
+            }
+            MenuValue::Grid(grid) => step_through(&GRIDS, &mut **grid, step),
+            MenuValue::Topology(topology) => step_through(&TOPOLOGIES, &mut **topology, step),
+            MenuValue::Neighbourhood(neighbourhood) => {
+                step_through(&Neighbourhood::ALL, &mut **neighbourhood, step)
+            }
+            MenuValue::Floors(floors) => step_through(&FLOORS, &mut **floors, step),
+            MenuValue::Binding(bindings, action) => {
+                let prev_button = *binding_mut(bindings, *action);
+                let mut button = prev_button;
+                step_through(&BINDABLE_BUTTONS, &mut button, step);
+
+                // Swap with the action that had the button, so no two actions share one
+                for other in 0..BINDING_COUNT {
+                    if *binding_mut(bindings, other) == button {
+                        *binding_mut(bindings, other) = prev_button;
+                    }
+                }
+                *binding_mut(bindings, *action) = button;
+            }
+            MenuValue::Theme(theme) => step_through(&Theme::ALL, &mut **theme, step),
+            MenuValue::NumberPalette(number_palette) => {
+                step_through(&NumberPalette::ALL, &mut **number_palette, step)
+            }
+        }
+    }
+}
+
+/// The button bound to the `action`th action, in the order they're listed in the menu
+fn binding_mut(bindings: &mut Bindings, action: usize) -> &mut Buttons {
+    match action {
+        0 => &mut bindings.reveal,
+        1 => &mut bindings.cycle,
+        2 => &mut bindings.flag,
+        3 => &mut bindings.chord,
+        _ => &mut bindings.jump,
+    }
+}
+
+/// Move `value` by `step` places through `values`, wrapping around at either end
+fn step_through<T: PartialEq + Copy>(values: &[T], value: &mut T, step: i32) {
+    let index = values.iter().position(|v| *v == *value).unwrap_or(0) as i32;
+    let next_index = (index + step).rem_euclid(values.len() as i32);
+    *value = values[next_index as usize];
+}
+
+/// Label and setting for each item in the menu
+fn menu_item(item: usize, options: &mut Options) -> (&'static str, MenuValue<'_>) {
+    match item {
+        0 => ("CURSOR WRAP", MenuValue::Toggle(&mut options.cursor_wrap)),
+        1 => (
+            "QUESTION MARKS",
+            MenuValue::Toggle(&mut options.question_marks),
+        ),
+        2 => (
+            "REVEAL ON PRESS",
+            MenuValue::Toggle(&mut options.reveal_on_press),
+        ),
+        3 => (
+            "REVEAL BUTTON",
+            MenuValue::Binding(&mut options.bindings, 0),
+        ),
+        4 => ("CYCLE BUTTON", MenuValue::Binding(&mut options.bindings, 1)),
+        5 => ("FLAG BUTTON", MenuValue::Binding(&mut options.bindings, 2)),
+        6 => ("CHORD BUTTON", MenuValue::Binding(&mut options.bindings, 3)),
+        7 => ("JUMP BUTTON", MenuValue::Binding(&mut options.bindings, 4)),
+        8 => (
+            "CASCADE SPEED",
+            MenuValue::CascadeSpeed(&mut options.cascade_speed),
+        ),
+        9 => ("PUZZLES", MenuValue::Toggle(&mut options.puzzles)),
+        10 => ("SHAPE", MenuValue::Shape(&mut options.shape)),
+        11 => ("GRID", MenuValue::Grid(&mut options.grid)),
+        12 => ("EDGES", MenuValue::Topology(&mut options.topology)),
+        13 => (
+            "COUNT",
+            MenuValue::Neighbourhood(&mut options.neighbourhood),
+        ),
+        14 => ("MULTI-MINES", MenuValue::Toggle(&mut options.multi_mines)),
+        15 => ("FLOORS", MenuValue::Floors(&mut options.floors)),
+        16 => ("CASUAL", MenuValue::Toggle(&mut options.casual)),
+        17 => ("LINE CLUES", MenuValue::Toggle(&mut options.line_clues)),
+        18 => ("THEME", MenuValue::Theme(&mut options.theme)),
+        19 => (
+            "NUMBER COLOURS",
+            MenuValue::NumberPalette(&mut options.number_palette),
+        ),
+        _ => ("NUMBER CUES", MenuValue::Toggle(&mut options.number_cues)),
+    }
+}
+
+/// Menu for changing the options, drawn on its own background
+pub struct OptionsMenu {
+    bg: RegularBackground,
+    font: &'static TileData,
+    selected: usize,
+    // First item shown at the top of the menu
+    scroll: usize,
+    // The value last skipped over, since it would count more mines than the numbers go up to
+    skipped: Option<&'static str>,
+}
+
+impl OptionsMenu {
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            bg: RegularBackground::new(
+                Priority::P0,
+                RegularBackgroundSize::Background32x32,
+                TileFormat::FourBpp,
+            ),
+            font: theme.gfx().font,
+            selected: 0,
+            scroll: 0,
+            skipped: None,
+        }
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) -> &mut Self {
+        self.font = theme.gfx().font;
+        self
+    }
+
+    fn draw_item(&mut self, item: usize, options: &Options) {
+        if !(self.scroll..self.scroll + VISIBLE_ITEM_COUNT).contains(&item) {
+            return;
+        }
+
+        let mut options = *options;
+        let (label, value) = menu_item(item, &mut options);
+        let cursor = if item == self.selected { ">" } else { " " };
+        let tile_pos = vec2(3, MENU_ITEMS_TILE_Y + (item - self.scroll) as i32);
+
+        // Pad the label, as scrolling can put a shorter one where a longer one was
+        draw_text(&mut self.bg, tile_pos, cursor, self.font);
+        draw_text(
+            &mut self.bg,
+            tile_pos + vec2(2, 0),
+            &format!("{label:LABEL_WIDTH$}"),
+            self.font,
+        );
+
+        // Right align the value, clearing whatever was there before
+        let value = value.text();
+        let value_pos = tile_pos + vec2(17, 0);
+        draw_text(&mut self.bg, value_pos, "       ", self.font);
+        draw_text(
+            &mut self.bg,
+            value_pos + vec2((VALUE_WIDTH - value.len()) as i32, 0),
+            value,
+            self.font,
+        );
+    }
+
+    pub fn draw(&mut self, options: &Options) {
+        draw_text(&mut self.bg, vec2(11, 1), "OPTIONS", self.font);
+        for item in self.scroll..self.scroll + VISIBLE_ITEM_COUNT {
+            self.draw_item(item, options);
+        }
+        self.draw_footer();
+    }
+
+    /// Say why a value was skipped, or how to leave the menu otherwise
+    fn draw_footer(&mut self) {
+        let footer = match self.skipped {
+            Some(value) => format!("{value} WOULD COUNT PAST {MAX_NUMBER}"),
+            None => String::from("START: BACK"),
+        };
+        debug_assert!(footer.len() <= FOOTER_WIDTH, "{footer} doesn't fit");
+        draw_text(
+            &mut self.bg,
+            vec2(3, 19),
+            &format!("{footer:FOOTER_WIDTH$}"),
+            self.font,
+        );
+    }
+
+    /// Handle player input, returns false once the menu has been closed
+    pub fn update(&mut self, button_controller: &ButtonController, options: &mut Options) -> bool {
+        if button_controller.is_just_pressed(Button::START)
+            || button_controller.is_just_pressed(Button::B)
+        {
+            return false;
+        }
+
+        // Move the selection between items
+        let prev_selected = self.selected;
+        let move_by = button_controller.just_pressed_y_tri() as i32;
+        self.selected =
+            (self.selected as i32 + move_by).rem_euclid(MENU_ITEM_COUNT as i32) as usize;
+        if self.selected != prev_selected {
+            self.draw_item(prev_selected, options);
+        }
+
+        // Scroll just far enough to keep the selection on screen
+        let prev_scroll = self.scroll;
+        self.scroll = self.scroll.clamp(
+            self.selected.saturating_sub(VISIBLE_ITEM_COUNT - 1),
+            self.selected,
+        );
+        if self.scroll != prev_scroll {
+            self.draw(options);
+        }
+
+        // Change the selected item, A always steps forwards
+        let step = match button_controller.just_pressed_x_tri() {
+            Tri::Zero if button_controller.is_just_pressed(Button::A) => 1,
+            x_tri => x_tri as i32,
+        };
+        let prev_skipped = self.skipped;
+        if self.selected != prev_selected {
+            self.skipped = None;
+        }
+        if step != 0 {
+            // Skip values with numbers too high to draw, coming back around to the old one at worst,
+            // and say which was skipped so it doesn't look like it's missing
+            self.skipped = None;
+            loop {
+                let (_, mut value) = menu_item(self.selected, options);
+                value.change(step);
+                let text = value.text();
+                if options.fits_numbers() {
+                    break;
+                }
+                self.skipped.get_or_insert(text);
+            }
+        }
+        self.draw_item(self.selected, options);
+        if self.skipped != prev_skipped {
+            self.draw_footer();
+        }
+
+        true
+    }
+
+    pub fn show(&self, frame: &mut GraphicsFrame) {
+        self.bg.show(frame);
+    }
+}
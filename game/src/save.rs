@@ -0,0 +1,96 @@
+use agb::save::{Error, SaveManager};
+
+use crate::{
+    level_select::{PROGRESS_SIZE, Progress},
+    number_palette::MAX_NUMBER,
+    options::{OPTIONS_SIZE, Options},
+    stats::{STATS_SIZE, Stats},
+};
+
+// Save data layout
+const SAVE_MAGIC: [u8; 4] = *b"MSW4";
+const OPTIONS_OFFSET: usize = SAVE_MAGIC.len();
+
+// Puzzle progress is kept apart from the options, leaving them room to grow
+const PROGRESS_MAGIC: [u8; 4] = *b"MSPZ";
+const PROGRESS_MAGIC_OFFSET: usize = 32;
+const PROGRESS_OFFSET: usize = PROGRESS_MAGIC_OFFSET + PROGRESS_MAGIC.len();
+
+// Statistics come after the puzzle progress, with their own magic for the same reason
+const STATS_MAGIC: [u8; 4] = *b"MSST";
+const STATS_MAGIC_OFFSET: usize = 64;
+const STATS_OFFSET: usize = STATS_MAGIC_OFFSET + STATS_MAGIC.len();
+
+/// Load the options from save data, or the default options if nothing has been saved yet
+pub fn load_options(save_manager: &mut SaveManager) -> Result<Options, Error> {
+    let mut save_data = save_manager.access()?;
+
+    let mut magic = [0u8; SAVE_MAGIC.len()];
+    save_data.read(0, &mut magic)?;
+    if magic != SAVE_MAGIC {
+        return Ok(Options::default());
+    }
+
+    let mut bytes = [0u8; OPTIONS_SIZE];
+    save_data.read(OPTIONS_OFFSET, &mut bytes)?;
+    let mut options = Options::from_bytes(&bytes);
+
+    // The menu never saves numbers which can't be drawn, so only a damaged save gets here
+    if !options.fits_numbers() {
+        agb::println!("Saved options count past {MAX_NUMBER} mines, going back to a single floor");
+        options.floors = 1;
+    }
+    Ok(options)
+}
+
+pub fn save_options(save_manager: &mut SaveManager, options: &Options) -> Result<(), Error> {
+    let mut save_data = save_manager.access()?;
+    let mut prepared = save_data.prepare_write(0..OPTIONS_OFFSET + OPTIONS_SIZE)?;
+    prepared.write(0, &SAVE_MAGIC)?;
+    prepared.write(OPTIONS_OFFSET, &options.to_bytes())
+}
+
+/// Load which puzzles have been completed, or none of them if nothing has been saved yet
+pub fn load_progress(save_manager: &mut SaveManager) -> Result<Progress, Error> {
+    let mut save_data = save_manager.access()?;
+
+    let mut magic = [0u8; PROGRESS_MAGIC.len()];
+    save_data.read(PROGRESS_MAGIC_OFFSET, &mut magic)?;
+    if magic != PROGRESS_MAGIC {
+        return Ok(Progress::default());
+    }
+
+    let mut bytes = [0u8; PROGRESS_SIZE];
+    save_data.read(PROGRESS_OFFSET, &mut bytes)?;
+    Ok(Progress::from_bytes(&bytes))
+}
+
+pub fn save_progress(save_manager: &mut SaveManager, progress: &Progress) -> Result<(), Error> {
+    let mut save_data = save_manager.access()?;
+    let mut prepared =
+        save_data.prepare_write(PROGRESS_MAGIC_OFFSET..PROGRESS_OFFSET + PROGRESS_SIZE)?;
+    prepared.write(PROGRESS_MAGIC_OFFSET, &PROGRESS_MAGIC)?;
+    prepared.write(PROGRESS_OFFSET, &progress.to_bytes())
+}
+
+/// Load the statistics, or empty ones if nothing has been saved yet
+pub fn load_stats(save_manager: &mut SaveManager) -> Result<Stats, Error> {
+    let mut save_data = save_manager.access()?;
+
+    let mut magic = [0u8; STATS_MAGIC.len()];
+    save_data.read(STATS_MAGIC_OFFSET, &mut magic)?;
+    if magic != STATS_MAGIC {
+        return Ok(Stats::default());
+    }
+
+    let mut bytes = [0u8; STATS_SIZE];
+    save_data.read(STATS_OFFSET, &mut bytes)?;
+    Ok(Stats::from_bytes(&bytes))
+}
+
+pub fn save_stats(save_manager: &mut SaveManager, stats: &Stats) -> Result<(), Error> {
+    let mut save_data = save_manager.access()?;
+    let mut prepared = save_data.prepare_write(STATS_MAGIC_OFFSET..STATS_OFFSET + STATS_SIZE)?;
+    prepared.write(STATS_MAGIC_OFFSET, &STATS_MAGIC)?;
+    prepared.write(STATS_OFFSET, &stats.to_bytes())
+}
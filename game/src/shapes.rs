@@ -0,0 +1,4 @@
+use minesweeper_board::{Puzzle, Shape};
+
+// The levels of tilemap/tilemap.ldtk as board shapes and puzzles, generated by build.rs
+include!(concat!(env!("OUT_DIR"), "/shapes.rs"));
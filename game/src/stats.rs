@@ -0,0 +1,47 @@
+// Number of bytes used to store the statistics in save data
+pub const STATS_SIZE: usize = 12;
+
+/// Records kept across games, which are saved as soon as a game ends
+#[derive(PartialEq, Eq, Clone, Copy, Default)]
+pub struct Stats {
+    pub wins: u32,
+    pub no_flag_wins: u32, // wins without a single flag placed by the player
+    pub best_score: u32,   // most blocks cleared in an endless game
+}
+
+impl Stats {
+    /// Count a win in which the player placed `flag_count` flags
+    pub fn record_win(&mut self, flag_count: u32) {
+        self.wins += 1;
+        if flag_count == 0 {
+            self.no_flag_wins += 1;
+        }
+    }
+
+    /// Keep `score` if it's the best so far, returning whether it was
+    pub fn record_score(&mut self, score: u32) -> bool {
+        let is_best = score > self.best_score;
+        if is_best {
+            self.best_score = score;
+        }
+        is_best
+    }
+
+    pub fn to_bytes(self) -> [u8; STATS_SIZE] {
+        let mut bytes = [0u8; STATS_SIZE];
+        bytes[0..4].copy_from_slice(&self.wins.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.no_flag_wins.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.best_score.to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; STATS_SIZE]) -> Self {
+        let word =
+            |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+        Self {
+            wins: word(0),
+            no_flag_wins: word(4),
+            best_score: word(8),
+        }
+    }
+}
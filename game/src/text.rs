@@ -0,0 +1,19 @@
+use agb::{
+    display::{tile_data::TileData, tiled::RegularBackground},
+    fixnum::Vector2D,
+};
+
+// Order of the glyphs in the font, one 8x8 tile each
+const GLYPHS: &str = " 0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-:!/<>.+";
+
+/// Draw `text` starting at `tile_pos`, unknown characters are drawn as spaces
+pub fn draw_text(bg: &mut RegularBackground, tile_pos: Vector2D<i32>, text: &str, font: &TileData) {
+    for (x, character) in text.chars().enumerate() {
+        let glyph_index = GLYPHS.find(character.to_ascii_uppercase()).unwrap_or(0);
+        bg.set_tile(
+            (tile_pos.x + x as i32, tile_pos.y),
+            &font.tiles,
+            font.tile_settings[glyph_index],
+        );
+    }
+}
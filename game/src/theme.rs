@@ -0,0 +1,175 @@
+use agb::{
+    display::{Palette16, Rgb, Rgb15, object::Sprite, tile_data::TileData},
+    include_aseprite, include_background_gfx,
+};
+
+// Background import, one module per theme so that each gets its own palettes
+include_background_gfx!(
+    mod classic_background,
+    "16171a",
+    BLOCKS => deduplicate "gfx/blocks.aseprite",
+    HEX_BLOCKS => deduplicate "gfx/hex_blocks.aseprite",
+    NUMBERS => deduplicate "gfx/numbers.aseprite",
+    FONT => deduplicate "gfx/font.aseprite",
+    CLUES => deduplicate "gfx/clues.aseprite",
+);
+
+include_background_gfx!(
+    mod grey_background,
+    "9d9d9d",
+    BLOCKS => deduplicate "gfx/themes/grey/blocks.aseprite",
+    HEX_BLOCKS => deduplicate "gfx/themes/grey/hex_blocks.aseprite",
+    NUMBERS => deduplicate "gfx/themes/grey/numbers.aseprite",
+    FONT => deduplicate "gfx/themes/grey/font.aseprite",
+    CLUES => deduplicate "gfx/themes/grey/clues.aseprite",
+);
+
+include_background_gfx!(
+    mod dark_background,
+    "0b0c0e",
+    BLOCKS => deduplicate "gfx/themes/dark/blocks.aseprite",
+    HEX_BLOCKS => deduplicate "gfx/themes/dark/hex_blocks.aseprite",
+    NUMBERS => deduplicate "gfx/themes/dark/numbers.aseprite",
+    FONT => deduplicate "gfx/themes/dark/font.aseprite",
+    CLUES => deduplicate "gfx/themes/dark/clues.aseprite",
+);
+
+include_background_gfx!(
+    mod retro_background,
+    "9bbc0f",
+    BLOCKS => deduplicate "gfx/themes/retro/blocks.aseprite",
+    HEX_BLOCKS => deduplicate "gfx/themes/retro/hex_blocks.aseprite",
+    NUMBERS => deduplicate "gfx/themes/retro/numbers.aseprite",
+    FONT => deduplicate "gfx/themes/retro/font.aseprite",
+    CLUES => deduplicate "gfx/themes/retro/clues.aseprite",
+);
+
+// Sprite import
+include_aseprite!(
+    mod classic_sprites,
+    "gfx/cursor.aseprite",
+);
+
+include_aseprite!(
+    mod grey_sprites,
+    "gfx/themes/grey/cursor.aseprite",
+);
+
+include_aseprite!(
+    mod dark_sprites,
+    "gfx/themes/dark/cursor.aseprite",
+);
+
+include_aseprite!(
+    mod retro_sprites,
+    "gfx/themes/retro/cursor.aseprite",
+);
+
+/// A look for the game, chosen from the options menu
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Theme {
+    #[default]
+    Classic,
+    Grey,
+    Dark,
+    Retro,
+}
+
+/// The art and palettes for drawing the game in a theme
+pub struct ThemeGfx {
+    pub palettes: &'static [Palette16],
+    pub blocks: &'static TileData,
+    pub hex_blocks: &'static TileData, // blocks cut into hexagons for the hex grid
+    pub numbers: &'static TileData,
+    pub font: &'static TileData,
+    pub clues: &'static TileData, // small digits for the mine totals beside the rows and columns
+    pub cursor: &'static Sprite,
+    pub number_colours: [Rgb15; 8], // the colours of the numbers 1 to 8 in `numbers`
+    pub light_backdrop: bool,
+}
+
+/// Convert a colour written as 0xRRGGBB for the GBA
+pub const fn colour(hex: u32) -> Rgb15 {
+    Rgb::new((hex >> 16) as u8, (hex >> 8) as u8, hex as u8).to_rgb15()
+}
+
+/// [`colour`] for a whole set of number colours
+pub const fn colours(hexes: [u32; 8]) -> [Rgb15; 8] {
+    let mut colours = [Rgb15::BLACK; 8];
+    let mut i = 0;
+    while i < 8 {
+        colours[i] = colour(hexes[i]);
+        i += 1;
+    }
+    colours
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [Theme::Classic, Theme::Grey, Theme::Dark, Theme::Retro];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Classic => "CLASSIC",
+            Theme::Grey => "GREY",
+            Theme::Dark => "DARK",
+            Theme::Retro => "RETRO",
+        }
+    }
+
+    pub fn gfx(self) -> ThemeGfx {
+        match self {
+            Theme::Classic => ThemeGfx {
+                palettes: classic_background::PALETTES,
+                blocks: &classic_background::BLOCKS,
+                hex_blocks: &classic_background::HEX_BLOCKS,
+                numbers: &classic_background::NUMBERS,
+                font: &classic_background::FONT,
+                clues: &classic_background::CLUES,
+                cursor: classic_sprites::CURSOR.sprite(0),
+                number_colours: colours([
+                    0xfafdff, 0x68aed4, 0x10d275, 0xbfff3c, 0xffd100, 0xff8426, 0xec380c, 0xbf0000,
+                ]),
+                light_backdrop: false,
+            },
+            Theme::Grey => ThemeGfx {
+                palettes: grey_background::PALETTES,
+                blocks: &grey_background::BLOCKS,
+                hex_blocks: &grey_background::HEX_BLOCKS,
+                numbers: &grey_background::NUMBERS,
+                font: &grey_background::FONT,
+                clues: &grey_background::CLUES,
+                cursor: grey_sprites::CURSOR.sprite(0),
+                number_colours: colours([
+                    0x0000ff, 0x007b00, 0xff0000, 0x00007b, 0x7b0000, 0x007b7b, 0x000000, 0x3c3c3c,
+                ]),
+                light_backdrop: true,
+            },
+            Theme::Dark => ThemeGfx {
+                palettes: dark_background::PALETTES,
+                blocks: &dark_background::BLOCKS,
+                hex_blocks: &dark_background::HEX_BLOCKS,
+                numbers: &dark_background::NUMBERS,
+                font: &dark_background::FONT,
+                clues: &dark_background::CLUES,
+                cursor: dark_sprites::CURSOR.sprite(0),
+                number_colours: colours([
+                    0x8ab4f8, 0x81c995, 0xf28b82, 0xc58af9, 0xfdd663, 0x78d9ec, 0xff8bcb, 0xe8eaed,
+                ]),
+                light_backdrop: false,
+            },
+            Theme::Retro => ThemeGfx {
+                palettes: retro_background::PALETTES,
+                blocks: &retro_background::BLOCKS,
+                hex_blocks: &retro_background::HEX_BLOCKS,
+                numbers: &retro_background::NUMBERS,
+                font: &retro_background::FONT,
+                clues: &retro_background::CLUES,
+                cursor: retro_sprites::CURSOR.sprite(0),
+                number_colours: colours([
+                    0x306230, 0x0f380f, 0x306230, 0x0f380f, 0x306230, 0x0f380f, 0x306230, 0x0f380f,
+                ]),
+                light_backdrop: true,
+            },
+        }
+    }
+}